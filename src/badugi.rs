@@ -0,0 +1,65 @@
+use crate::data::{Badugi, Card, CardSet, Deck, Game, PokerError, Rank};
+use crate::low::ace_low_ranks;
+
+/// Ranks from ace to king, ace as bit zero.
+const RANK_MASK: u32 = 0x1FFF;
+
+impl CardSet {
+    /// Best badugi among the cards, the largest set of them with no two of
+    /// the same rank or suit, the lowest on equal size.
+    pub fn badugi(&self) -> Badugi {
+        let cards: Vec<Card> = self.cards().collect();
+        (0..1u32 << cards.len())
+            .filter_map(|subset| {
+                let mut ranks: u32 = 0;
+                let mut suits = 0;
+                let chosen = (0..cards.len()).filter(|i| subset >> i & 1 == 1);
+                for card in chosen.map(|i| cards[i]) {
+                    let rank = 1 << ((card.rank as u32 + 1) % 13);
+                    let suit = 1 << card.suit as u32;
+                    if ranks & rank != 0 || suits & suit != 0 {
+                        return None;
+                    }
+                    ranks |= rank;
+                    suits |= suit;
+                }
+                Some(Badugi((ranks.count_ones() << 13) | (RANK_MASK - ranks)))
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Badugi {
+    /// Number of cards that play, four for a full badugi.
+    pub fn len(&self) -> usize {
+        (self.0 >> 13) as usize
+    }
+
+    /// Whether there are no cards, which only an empty hand makes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ranks of the cards that play, highest first.
+    pub fn ranks(&self) -> Vec<Rank> {
+        match self.is_empty() {
+            true => vec![],
+            false => ace_low_ranks(RANK_MASK - (self.0 & RANK_MASK)),
+        }
+    }
+}
+
+impl Deck {
+    /// Best badugi of a four card hand, panics on invalid input.
+    pub fn badugi(hand: &Deck) -> Badugi {
+        Self::try_badugi(hand).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `badugi`, but fails unless the hand holds four different cards.
+    pub fn try_badugi(hand: &Deck) -> Result<Badugi, PokerError> {
+        Game::Badugi.check_hand(hand)?;
+        Self::check_unique([hand])?;
+        Ok(CardSet::from(hand).badugi())
+    }
+}
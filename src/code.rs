@@ -1,12 +1,18 @@
-use crate::data::{Card, Deck, DeckFormatter, Hand, Rank, Suit};
+use crate::data::{
+    Badugi, Card, CardSet, Deck, DeckFormatter, Hand, Low, PokerError, Rank, Rules, StudHand, Suit,
+};
 use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
 use std::convert::TryInto;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::vec;
 use strum::IntoEnumIterator;
 
-#[allow(dead_code)]
 impl Deck {
+    /// The standard 52 card deck, ordered by rank.
     pub fn french() -> Self {
         let suits = Suit::iter();
         let ranks = Rank::iter();
@@ -17,14 +23,12 @@ impl Deck {
         Deck { cards }
     }
 
+    /// The 36 card short deck, sixes to aces.
     pub fn russian() -> Self {
         let suits = Suit::iter();
         let ranks = Rank::iter().filter(|rank| {
             use Rank::*;
-            match rank {
-                Two | Three | Four | Five => false,
-                _ => true,
-            }
+            !matches!(rank, Two | Three | Four | Five)
         });
         let cards = itertools::iproduct!(ranks, suits)
             .map(|(rank, suit)| Card { rank, suit })
@@ -33,55 +37,177 @@ impl Deck {
         Deck { cards }
     }
 
-    pub fn shuffle(mut self) -> Self {
+    /// Shuffles with the thread local generator.
+    pub fn shuffle(self) -> Self {
+        self.shuffle_with(&mut rand::thread_rng())
+    }
+
+    /// Shuffles with `rng`, for reproducible decks.
+    pub fn shuffle_with<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
         use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        self.cards.shuffle(&mut rng);
+        self.cards.shuffle(rng);
         self
     }
 
+    /// Shuffles with a generator seeded from `seed`, the same seed always
+    /// gives the same order.
+    pub fn shuffle_seeded(self, seed: u64) -> Self {
+        self.shuffle_with(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Cards sorted by rank, then suit.
     pub fn sort(mut self) -> Self {
         self.cards.sort();
         self
     }
 
+    /// Takes `n` cards off the top, panics when fewer are left.
     pub fn deal(&mut self, n: usize) -> Self {
         let cards = self.cards.drain(0..n).collect::<Vec<_>>();
         Deck { cards }
     }
 
+    /// Takes `n` cards off the top.
+    pub fn try_deal(&mut self, n: usize) -> Result<Self, PokerError> {
+        if n > self.cards.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested: n,
+                left: self.cards.len(),
+            });
+        }
+        Ok(self.deal(n))
+    }
+
+    /// Deals `n` random cards without shuffling the rest of the deck.
+    pub fn deal_with<R: Rng + ?Sized>(&mut self, n: usize, rng: &mut R) -> Self {
+        use rand::seq::SliceRandom;
+        let (cards, _) = self.cards.partial_shuffle(rng, n);
+        let cards = cards.to_vec();
+        self.cards.retain(|card| !cards.contains(card));
+        Deck { cards }
+    }
+
+    /// Parses cards written back to back or apart, like "AhKd7c",
+    /// "Ts 10d tc" or "A ♥, K ♦". Ranks are 2-9, T or 10, J, Q, K, A in any
+    /// case, suits are s, h, d, c or their glyphs.
+    pub fn parse(s: &str) -> Result<Self, PokerError> {
+        let chars: Vec<char> = s.chars().collect();
+        let error = |position| (s.to_string(), position);
+        let mut cards = vec![];
+        let mut i = 0;
+
+        loop {
+            while i < chars.len() && (chars[i].is_whitespace() || chars[i] == ',') {
+                i += 1;
+            }
+            if i == chars.len() {
+                break;
+            }
+
+            let rank = if chars[i] == '1' && chars.get(i + 1) == Some(&'0') {
+                i += 1;
+                Rank::Ten
+            } else {
+                Rank::from_char(chars[i]).ok_or_else(|| {
+                    let (input, position) = error(i);
+                    PokerError::UnknownRank { input, position }
+                })?
+            };
+            i += 1;
+
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            let Some(&suit) = chars.get(i) else {
+                return Err(PokerError::MissingSuit {
+                    input: s.to_string(),
+                });
+            };
+            let suit = Suit::from_char(suit).ok_or_else(|| {
+                let (input, position) = error(i);
+                PokerError::UnknownSuit { input, position }
+            })?;
+            i += 1;
+
+            cards.push(Card { rank, suit });
+        }
+
+        Ok(Deck { cards })
+    }
+
+    /// Displays the cards `rows` to a line.
     pub fn rows<'a>(&'a self, rows: &'a usize) -> DeckFormatter<'a> {
         DeckFormatter { deck: self, rows }
     }
 
+    /// Appends the cards of `other`.
     pub fn merge(mut self, other: Self) -> Self {
-        self.cards.extend(other.cards.into_iter());
+        self.cards.extend(other.cards);
         self
     }
 
+    /// Best hand of two hole cards on a board of three to five cards,
+    /// panics on invalid input.
     pub fn best_hand(table: &Deck, hand: Deck) -> Hand {
-        let Some(hand) = Self::hand_iter(table, hand).next() else {
-            unreachable!("One card should always be present");
-        };
+        Self::try_best_hand(table, hand).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Best hand of two hole cards on a board of three to five cards.
+    pub fn try_best_hand(table: &Deck, hand: Deck) -> Result<Hand, PokerError> {
+        Self::try_best_hand_with(table, hand, Rules::Standard)
+    }
+
+    /// Best hand under `rules`, panics on invalid input.
+    pub fn best_hand_with(table: &Deck, hand: Deck, rules: Rules) -> Hand {
+        Self::try_best_hand_with(table, hand, rules).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        hand
+    /// Best hand under `rules`, like A-6-7-8-9 in a short deck.
+    pub fn try_best_hand_with(table: &Deck, hand: Deck, rules: Rules) -> Result<Hand, PokerError> {
+        Self::check_holding(table, &hand)?;
+        Ok((CardSet::from(table) | CardSet::from(&hand)).best_hand_with(rules))
     }
 
+    /// Every hand the cards make, best first, panics on invalid input.
     pub fn all_hands(table: &Deck, hand: Deck) -> Vec<Hand> {
-        Self::hand_iter(table, hand).collect()
+        Self::try_all_hands(table, hand).unwrap_or_else(|err| panic!("{}", err))
     }
 
-    fn hand_iter(table: &Deck, hand: Deck) -> impl Iterator<Item = Hand> {
+    /// Every hand the cards make, best first.
+    pub fn try_all_hands(table: &Deck, hand: Deck) -> Result<Vec<Hand>, PokerError> {
+        Self::check_holding(table, &hand)?;
+        Ok(Self::hand_iter(table, hand).collect())
+    }
+
+    pub(crate) fn check_holding(table: &Deck, hand: &Deck) -> Result<(), PokerError> {
         if hand.cards.len() != 2 {
-            panic!("Hand must have exactly 2 cards");
+            return Err(PokerError::HandSize(hand.cards.len()));
         }
 
-        let table_has_3_to_5_cards =
-            3 <= table.cards.len() && table.cards.len() <= 5;
+        let table_has_3_to_5_cards = 3 <= table.cards.len() && table.cards.len() <= 5;
         if !table_has_3_to_5_cards {
-            panic!("Table must have between 3 and 5 cards");
+            return Err(PokerError::TableSize(table.cards.len()));
+        }
+
+        Self::check_unique([table, hand])
+    }
+
+    /// Fails on the first card that shows up twice across the decks.
+    pub(crate) fn check_unique<'a, I>(decks: I) -> Result<(), PokerError>
+    where
+        I: IntoIterator<Item = &'a Deck>,
+    {
+        let mut seen = CardSet::default();
+        for card in decks.into_iter().flat_map(|deck| deck.cards.iter()) {
+            if seen.contains(*card) {
+                return Err(PokerError::DuplicateCard(*card));
+            }
+            seen.insert(*card);
         }
+        Ok(())
+    }
 
+    fn hand_iter(table: &Deck, hand: Deck) -> impl Iterator<Item = Hand> {
         let table = table.clone().merge(hand).sort();
         let hands = vec![
             Self::royal_flush,
@@ -96,40 +222,43 @@ impl Deck {
             Self::highest_card,
         ];
 
-        let hands = hands
-            .into_iter()
-            .filter_map(move |hand| hand(&table));
-
-        hands
+        hands.into_iter().filter_map(move |hand| hand(&table))
     }
 
-    fn same_rank<const N: usize>(cards: &Vec<Card>) -> Option<[Card; N]> {
+    fn same_rank<const N: usize>(cards: &[Card]) -> Option<[Card; N]> {
         cards
             .windows(N)
-            .filter(|cards| cards.iter().all(|card| card.rank == cards[0].rank))
-            .last()
-            .map(|cards| cards.to_owned().try_into().ok())
-            .flatten()
+            .rfind(|cards| cards.iter().all(|card| card.rank == cards[0].rank))
+            .and_then(|cards| cards.to_owned().try_into().ok())
     }
 
-    fn same_suit<const N: usize>(cards: &Vec<Card>) -> Option<[Card; N]> {
+    /// Highest `N` cards not already used by the category, best first.
+    fn kickers<const N: usize>(cards: &[Card], used: &[Card]) -> Option<[Card; N]> {
+        cards
+            .iter()
+            .rev()
+            .filter(|card| !used.contains(card))
+            .take(N)
+            .cloned()
+            .collect::<Vec<_>>()
+            .try_into()
+            .ok()
+    }
+
+    fn same_suit<const N: usize>(cards: &[Card]) -> Option<[Card; N]> {
         let cards: Vec<_> = cards
             .iter()
             .sorted_by_key(|card| card.suit)
             .cloned()
             .collect();
 
-        let cards = cards
-            .windows(N)
-            .filter(|cards| cards.iter().all(|card| card.suit == cards[0].suit))
-            .last()
-            .map(|cards| cards.to_owned().try_into().ok())
-            .flatten();
-
         cards
+            .windows(N)
+            .rfind(|cards| cards.iter().all(|card| card.suit == cards[0].suit))
+            .and_then(|cards| cards.to_owned().try_into().ok())
     }
 
-    fn consecutive<const N: usize>(cards: &Vec<Card>) -> Option<[Card; N]> {
+    fn consecutive<const N: usize>(cards: &[Card]) -> Option<[Card; N]> {
         let diffs = cards
             .windows(2)
             .enumerate()
@@ -138,23 +267,23 @@ impl Deck {
 
         let (idx, _) = diffs
             .windows(N - 1)
-            .filter(|diffs| diffs.iter().all(|(_, diff)| *diff == 1 || *diff == -12))
-            .last()?
+            .rfind(|diffs| diffs.iter().all(|(_, diff)| *diff == 1 || *diff == -12))?
             .first()?
             .to_owned();
 
-        let straight: [Card; N] = cards[idx..idx + N].to_owned().try_into().ok()?;
-
-        return Some(straight);
+        cards[idx..idx + N].to_owned().try_into().ok()
     }
 
     fn highest_card(&self) -> Option<Hand> {
-        self.cards.last().cloned().map(Hand::HighCard)
+        let card = *self.cards.last()?;
+        let kickers: [Card; 4] = Self::kickers(&self.cards, &[card])?;
+        Some(Hand::HighCard(card, kickers))
     }
 
     fn pair(&self) -> Option<Hand> {
         let pair: [Card; 2] = Self::same_rank(&self.cards)?;
-        Some(Hand::Pair(pair))
+        let kickers: [Card; 3] = Self::kickers(&self.cards, &pair)?;
+        Some(Hand::Pair(pair, kickers))
     }
 
     fn two_pairs(&self) -> Option<Hand> {
@@ -164,21 +293,27 @@ impl Deck {
         cards.retain(|card| card.rank != first_pair[0].rank);
         let second_pair: [Card; 2] = Self::same_rank(&cards)?;
 
-        Some(Hand::TwoPair(first_pair, second_pair))
+        let used = [first_pair, second_pair].concat();
+        let [kicker]: [Card; 1] = Self::kickers(&self.cards, &used)?;
+
+        Some(Hand::TwoPair(first_pair, second_pair, kicker))
     }
 
     fn three_of_a_kind(&self) -> Option<Hand> {
         let three_of_a_kind: [Card; 3] = Self::same_rank(&self.cards)?;
-        Some(Hand::ThreeOfAKind(three_of_a_kind))
+        let kickers: [Card; 2] = Self::kickers(&self.cards, &three_of_a_kind)?;
+        Some(Hand::ThreeOfAKind(three_of_a_kind, kickers))
     }
 
     fn straight(&self) -> Option<Hand> {
-        if let Some(straight) = Self::consecutive(&self.cards) {
+        let mut cards = self.cards.clone();
+        cards.dedup_by_key(|card| card.rank);
+
+        if let Some(straight) = Self::consecutive(&cards) {
             return Some(Hand::Straight(straight));
         }
 
-        if self.cards.first()?.rank == Rank::Two && self.cards.last()?.rank == Rank::Ace {
-            let mut cards = self.cards.clone();
+        if cards.first()?.rank == Rank::Two && cards.last()?.rank == Rank::Ace {
             let ace = cards.pop()?;
             cards.insert(0, ace);
             let straight: [Card; 5] = Self::consecutive(&cards)?;
@@ -204,18 +339,20 @@ impl Deck {
     }
 
     fn four_of_a_kind(&self) -> Option<Hand> {
-        self.cards
-            .windows(4)
-            .find(|cards| cards.iter().all(|card| card.rank == cards[0].rank))
-            .map(|cards| cards.to_owned().try_into().ok())
-            .map(|cards| cards.map(Hand::FourOfAKind))
-            .flatten()
+        let four_of_a_kind: [Card; 4] = Self::same_rank(&self.cards)?;
+        let [kicker]: [Card; 1] = Self::kickers(&self.cards, &four_of_a_kind)?;
+        Some(Hand::FourOfAKind(four_of_a_kind, kicker))
     }
 
     fn straight_flush(&self) -> Option<Hand> {
-        let flush: [Card; 5] = Self::same_suit(&self.cards)?;
+        let [card, ..]: [Card; 5] = Self::same_suit(&self.cards)?;
         let deck = Deck {
-            cards: flush.to_vec(),
+            cards: self
+                .cards
+                .iter()
+                .filter(|other| other.suit == card.suit)
+                .cloned()
+                .collect(),
         };
         if let Hand::Straight(straight) = deck.straight()? {
             return Some(Hand::StraightFlush(straight));
@@ -233,95 +370,165 @@ impl Deck {
     }
 }
 
-impl Card {
-    pub fn new(rank: Rank, suit: Suit) -> Self {
-        Card { rank, suit }
-    }
-
-    pub fn try_from(s: &str) -> Option<Self> {
+impl Rank {
+    /// Single character rank used by range and hand history notation.
+    pub fn from_char(c: char) -> Option<Self> {
         use Rank::*;
-        use Suit::*;
-
-        let mut split_at = s.split(" ");
-        let (rank, suit) = (split_at.next()?, split_at.next()?);
-
-        let rank = match rank {
-            "2" => Two,
-            "3" => Three,
-            "4" => Four,
-            "5" => Five,
-            "6" => Six,
-            "7" => Seven,
-            "8" => Eight,
-            "9" => Nine,
-            "10" => Ten,
-            "J" => Jack,
-            "Q" => Queen,
-            "K" => King,
-            "A" => Ace,
+        let rank = match c.to_ascii_uppercase() {
+            '2' => Two,
+            '3' => Three,
+            '4' => Four,
+            '5' => Five,
+            '6' => Six,
+            '7' => Seven,
+            '8' => Eight,
+            '9' => Nine,
+            'T' => Ten,
+            'J' => Jack,
+            'Q' => Queen,
+            'K' => King,
+            'A' => Ace,
             _ => return None,
         };
+        Some(rank)
+    }
 
-        let suit = match suit {
-            "♠" => Spades,
-            "♥" => Hearts,
-            "♦" => Diamonds,
-            "♣" => Clubs,
+    /// ASCII rank letter, "T" for ten.
+    pub fn to_char(self) -> char {
+        b"23456789TJQKA"[self as usize] as char
+    }
+}
+
+impl Suit {
+    /// Suit of a letter or glyph, like 'h' or '♥'.
+    pub fn from_char(c: char) -> Option<Self> {
+        use Suit::*;
+        let suit = match c.to_ascii_lowercase() {
+            's' | '♠' => Spades,
+            'h' | '♥' => Hearts,
+            'd' | '♦' => Diamonds,
+            'c' | '♣' => Clubs,
             _ => return None,
         };
+        Some(suit)
+    }
 
-        Some(Card { rank, suit })
+    /// Lowercase ASCII suit letter.
+    pub fn to_char(self) -> char {
+        b"shdc"[self as usize] as char
     }
 }
 
-impl Display for Hand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Card {
+    /// Card of `rank` and `suit`.
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card { rank, suit }
+    }
+
+    /// Parses a single card in any notation `Deck::parse` accepts.
+    pub fn parse(s: &str) -> Result<Self, PokerError> {
+        match Deck::parse(s)?.cards[..] {
+            [card] => Ok(card),
+            ref cards => Err(PokerError::CardCount {
+                input: s.to_string(),
+                expected: 1,
+                found: cards.len(),
+            }),
+        }
+    }
+}
+
+impl Hand {
+    /// All five cards of the hand: the category cards first, then kickers.
+    pub fn cards(&self) -> [Card; 5] {
+        use Hand::*;
+        match *self {
+            HighCard(card, k) => [card, k[0], k[1], k[2], k[3]],
+            Pair(p, k) => [p[0], p[1], k[0], k[1], k[2]],
+            TwoPair(p1, p2, k) => [p1[0], p1[1], p2[0], p2[1], k],
+            ThreeOfAKind(t, k) => [t[0], t[1], t[2], k[0], k[1]],
+            FullHouse(t, p) => [t[0], t[1], t[2], p[0], p[1]],
+            FourOfAKind(q, k) => [q[0], q[1], q[2], q[3], k],
+            Straight(cards) | Flush(cards) | StraightFlush(cards) | RoyalFlush(cards) => cards,
+        }
+    }
+
+    /// Ranks in the order they decide a tie between hands of the same category.
+    pub(crate) fn ranks(&self) -> [Rank; 5] {
+        use Hand::*;
+        let mut cards = self.cards();
+        if let Straight(_) | Flush(_) | StraightFlush(_) | RoyalFlush(_) = self {
+            // Stored low to high, the wheel starts with its ace
+            cards.reverse();
+        }
+        cards.map(|card| card.rank)
+    }
+
+    /// Name of the category, like "FullHouse".
+    pub fn name(&self) -> &'static str {
         use Hand::*;
         match self {
-            HighCard(card) => write!(f, "HighCard({})", card),
-            Pair(cards) => write!(f, "Pair({} {})", cards[0], cards[1]),
-            TwoPair(cards1, cards2) => write!(
-                f,
-                "TwoPair({} {} {} {})",
-                cards1[0], cards1[1], cards2[0], cards2[1]
-            ),
-            ThreeOfAKind(cards) => {
-                write!(f, "ThreeOfAKind({} {} {})", cards[0], cards[1], cards[2])
-            }
-            Straight(cards) => write!(
-                f,
-                "Straight({} {} {} {} {})",
-                cards[0], cards[1], cards[2], cards[3], cards[4]
-            ),
-            Flush(cards) => write!(
-                f,
-                "Flush({} {} {} {} {})",
-                cards[0], cards[1], cards[2], cards[3], cards[4]
-            ),
-            FullHouse(cards1, cards2) => write!(
-                f,
-                "FullHouse({} {} {} {} {})",
-                cards1[0], cards1[1], cards1[2], cards2[0], cards2[1]
-            ),
-            FourOfAKind(cards) => write!(
-                f,
-                "FourOfAKind({} {} {} {})",
-                cards[0], cards[1], cards[2], cards[3]
-            ),
-            StraightFlush(cards) => write!(
-                f,
-                "StraightFlush({} {} {} {} {})",
-                cards[0], cards[1], cards[2], cards[3], cards[4]
-            ),
-            RoyalFlush(cards) => write!(
-                f,
-                "RoyalFlush({} {} {} {} {})",
-                cards[0], cards[1], cards[2], cards[3], cards[4]
-            ),
+            HighCard(..) => "HighCard",
+            Pair(..) => "Pair",
+            TwoPair(..) => "TwoPair",
+            ThreeOfAKind(..) => "ThreeOfAKind",
+            Straight(..) => "Straight",
+            Flush(..) => "Flush",
+            FullHouse(..) => "FullHouse",
+            FourOfAKind(..) => "FourOfAKind",
+            StraightFlush(..) => "StraightFlush",
+            RoyalFlush(..) => "RoyalFlush",
         }
     }
 }
 
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.strength().cmp(&other.strength())
+    }
+}
+
+impl Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cards = self.cards();
+        let cards = match f.alternate() {
+            true => cards.iter().map(|card| format!("{:#}", card)).join(" "),
+            false => cards.iter().join(" "),
+        };
+        write!(f, "{}({})", self.name(), cards)
+    }
+}
+
+/// Ranks of the badugi from the top, like "K-7-4-A".
+impl Display for Badugi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranks = self.ranks().iter().map(|rank| rank.to_char()).join("-");
+        write!(f, "{}", ranks)
+    }
+}
+
+/// Ranks of the low from the top, like "8-5-4-3-A".
+impl Display for Low {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranks = self.ranks().iter().map(|rank| rank.to_char()).join("-");
+        write!(f, "{}", ranks)
+    }
+}
+
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Rank::*;
@@ -347,17 +554,103 @@ impl Display for Card {
             Diamonds => "♦",
             Clubs => "♣",
         };
+        if f.alternate() {
+            return write!(f, "{}{}", self.rank.to_char(), self.suit.to_char());
+        }
         write!(f, "{} {}", rank, suit)
     }
 }
 
+/// Cards separated by spaces, `{:#}` writes them back to back in ASCII
+/// like "AhKd7c".
+impl Display for Deck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(
+                f,
+                "{}",
+                self.cards.iter().map(|card| format!("{:#}", card)).join("")
+            );
+        }
+        write!(f, "{}", self.cards.iter().join(" "))
+    }
+}
+
+/// Down cards, a slash, then up cards. `{:#}` writes them like "AsKd/Qh".
+impl Display for StudHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(f, "{:#}/{:#}", self.down, self.up);
+        }
+        write!(f, "{} / {}", self.down, self.up)
+    }
+}
+
+impl Display for PokerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use PokerError::*;
+        match self {
+            HandSize(n) => write!(f, "Hand must have exactly 2 cards, got {}", n),
+            TableSize(n) => write!(f, "Table must have between 3 and 5 cards, got {}", n),
+            BoardSize(n) => write!(f, "Board must have at most 5 cards, got {}", n),
+            OutsBoard(n) => write!(f, "Outs need a flop or a turn, got a board of {} cards", n),
+            OmahaHandSize(n) => write!(f, "Omaha hand must have 4 to 6 cards, got {}", n),
+            LowballHandSize(n) => write!(f, "Lowball hand must have 5 to 7 cards, got {}", n),
+            BadugiHandSize(n) => write!(f, "Badugi hand must have exactly 4 cards, got {}", n),
+            NoBoard => write!(f, "Equity needs a game with a board, like hold'em or Omaha"),
+            StudHandSize(n) => write!(f, "Stud hand must have at most 7 cards, got {}", n),
+            StudStreet(n) => write!(f, "Stud streets run from third to seventh, got {}", n),
+            UnsupportedGame(game) => write!(f, "Ranges are only played in hold'em, got {:?}", game),
+            EmptyRange(player) => write!(
+                f,
+                "Range of player {} has no combo left after removing known cards",
+                player + 1
+            ),
+            NoPlayers => write!(f, "Simulation needs at least one player"),
+            RangesConflict => write!(f, "Ranges leave no deal without shared cards"),
+            NotEnoughCards { requested, left } => write!(
+                f,
+                "Cannot deal {} cards, only {} left in the deck",
+                requested, left
+            ),
+            DuplicateCard(card) => write!(f, "Card {} is used more than once", card),
+            UnknownRank { input, position } => write!(
+                f,
+                "Unknown rank at position {} of {:?}, expected 2-9, T, 10, J, Q, K or A",
+                position, input
+            ),
+            UnknownSuit { input, position } => write!(
+                f,
+                "Unknown suit at position {} of {:?}, expected s, h, d, c, ♠, ♥, ♦ or ♣",
+                position, input
+            ),
+            MissingSuit { input } => write!(f, "Missing suit at the end of {:?}", input),
+            InvalidHand(input) => {
+                write!(f, "{:?} is not a hand like \"Pair(As Ad Kh 3h 2h)\"", input)
+            }
+            CardCount {
+                input,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} card(s) in {:?}, found {}",
+                expected, input, found
+            ),
+            InvalidOdds(reason) => write!(f, "Invalid pot odds: {}", reason),
+            LookupFile(reason) => write!(f, "Lookup table cache failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PokerError {}
+
 impl<'a> Display for DeckFormatter<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut cards = self.deck.cards.iter();
         let rows = *self.rows;
 
         while cards.len() > 0 {
-
             if let Some(card) = cards.next() {
                 write!(f, "{}", card)?;
             }
@@ -377,6 +670,177 @@ impl<'a> Display for DeckFormatter<'a> {
     }
 }
 
+impl FromStr for Rank {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || PokerError::UnknownRank {
+            input: s.to_string(),
+            position: 0,
+        };
+        match s.chars().collect::<Vec<_>>()[..] {
+            ['1', '0'] => Ok(Rank::Ten),
+            [c] => Rank::from_char(c).ok_or_else(unknown),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl FromStr for Suit {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || PokerError::UnknownSuit {
+            input: s.to_string(),
+            position: 0,
+        };
+        match s.chars().collect::<Vec<_>>()[..] {
+            [c] => Suit::from_char(c).ok_or_else(unknown),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Card::parse(s)
+    }
+}
+
+impl FromStr for Deck {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Deck::parse(s)
+    }
+}
+
+impl StudHand {
+    /// Parses down cards, then up cards after a slash, like "AsKd/Qh7c".
+    /// Without a slash every card is down, "/Qh7c" shows only up cards.
+    pub fn parse(s: &str) -> Result<Self, PokerError> {
+        let (down, up) = s.split_once('/').unwrap_or((s, ""));
+        Ok(StudHand {
+            down: Deck::parse(down)?,
+            up: Deck::parse(up)?,
+        })
+    }
+}
+
+impl FromStr for StudHand {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StudHand::parse(s)
+    }
+}
+
+/// Reads back the `Display` output of a hand, like
+/// "FullHouse(K ♠ K ♥ K ♦ 3 ♣ 3 ♥)" or "Pair(As Ad Kh 3h 2h)". The cards
+/// have to make the named hand, in the order `Display` writes them.
+impl FromStr for Hand {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Hand::*;
+
+        let invalid = || PokerError::InvalidHand(s.to_string());
+
+        let (name, cards) = s
+            .trim()
+            .strip_suffix(')')
+            .and_then(|hand| hand.split_once('('))
+            .ok_or_else(invalid)?;
+
+        let cards = Deck::parse(cards)?;
+        Deck::check_unique([&cards])?;
+        let found = cards.cards.len();
+        let [a, b, c, d, e]: [Card; 5] =
+            cards.cards.try_into().map_err(|_| PokerError::CardCount {
+                input: s.to_string(),
+                expected: 5,
+                found,
+            })?;
+
+        let hand = match name.trim() {
+            "HighCard" => HighCard(a, [b, c, d, e]),
+            "Pair" => Pair([a, b], [c, d, e]),
+            "TwoPair" => TwoPair([a, b], [c, d], e),
+            "ThreeOfAKind" => ThreeOfAKind([a, b, c], [d, e]),
+            "Straight" => Straight([a, b, c, d, e]),
+            "Flush" => Flush([a, b, c, d, e]),
+            "FullHouse" => FullHouse([a, b, c], [d, e]),
+            "FourOfAKind" => FourOfAKind([a, b, c, d], e),
+            "StraightFlush" => StraightFlush([a, b, c, d, e]),
+            "RoyalFlush" => RoyalFlush([a, b, c, d, e]),
+            _ => return Err(invalid()),
+        };
+
+        // A hand is valid if some rules make it the best of its cards, like
+        // the A-6-7-8-9 straight in a short deck.
+        let set: CardSet = hand.cards().into_iter().collect();
+        let rules = [
+            Rules::Standard,
+            Rules::ShortDeck {
+                trips_beat_straight: false,
+            },
+            Rules::ShortDeck {
+                trips_beat_straight: true,
+            },
+        ];
+        if !rules
+            .into_iter()
+            .any(|rules| set.evaluate_with(rules) == hand.strength_with(rules))
+        {
+            return Err(invalid());
+        }
+
+        Ok(hand)
+    }
+}
+
+impl TryFrom<&str> for Rank {
+    type Error = PokerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&str> for Suit {
+    type Error = PokerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = PokerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&str> for Deck {
+    type Error = PokerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&str> for Hand {
+    type Error = PokerError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 impl From<Vec<Card>> for Deck {
     fn from(cards: Vec<Card>) -> Self {
         Deck { cards }
@@ -387,14 +851,14 @@ impl From<Hand> for usize {
     fn from(hand: Hand) -> Self {
         use Hand::*;
         match hand {
-            HighCard(_) => 0,
-            Pair(_) => 1,
-            TwoPair(_, _) => 2,
-            ThreeOfAKind(_) => 3,
+            HighCard(_, _) => 0,
+            Pair(_, _) => 1,
+            TwoPair(_, _, _) => 2,
+            ThreeOfAKind(_, _) => 3,
             Straight(_) => 4,
             Flush(_) => 5,
             FullHouse(_, _) => 6,
-            FourOfAKind(_) => 7,
+            FourOfAKind(_, _) => 7,
             StraightFlush(_) => 8,
             RoyalFlush(_) => 9,
         }
@@ -407,14 +871,14 @@ impl From<usize> for Hand {
         let cards = [Card::default(); 5];
 
         match idx {
-            0 => HighCard(cards[0]),
-            1 => Pair([cards[0], cards[1]]),
-            2 => TwoPair([cards[0], cards[1]], [cards[2], cards[3]]),
-            3 => ThreeOfAKind([cards[0], cards[1], cards[2]]),
+            0 => HighCard(cards[0], [cards[1], cards[2], cards[3], cards[4]]),
+            1 => Pair([cards[0], cards[1]], [cards[2], cards[3], cards[4]]),
+            2 => TwoPair([cards[0], cards[1]], [cards[2], cards[3]], cards[4]),
+            3 => ThreeOfAKind([cards[0], cards[1], cards[2]], [cards[3], cards[4]]),
             4 => Straight(cards),
             5 => Flush(cards),
             6 => FullHouse([cards[0], cards[1], cards[2]], [cards[3], cards[4]]),
-            7 => FourOfAKind([cards[0], cards[1], cards[2], cards[3]]),
+            7 => FourOfAKind([cards[0], cards[1], cards[2], cards[3]], cards[4]),
             8 => StraightFlush(cards),
             9 => RoyalFlush(cards),
             _ => unreachable!(),
@@ -1,56 +1,586 @@
+use std::time::Duration;
 use strum_macros::EnumIter;
 
+/// Suit of a card. Suits never rank hands, the order only sorts cards.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, EnumIter, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Suit {
+    /// Written "s" or "♠".
     Spades,
+    /// Written "h" or "♥".
     Hearts,
+    /// Written "d" or "♦".
     Diamonds,
+    /// Written "c" or "♣".
     Clubs,
 }
 
+/// Rank of a card, from deuce to ace.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, EnumIter, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Rank {
+    /// Written "2".
     Two,
+    /// Written "3".
     Three,
+    /// Written "4".
     Four,
+    /// Written "5".
     Five,
+    /// Written "6".
     Six,
+    /// Written "7".
     Seven,
+    /// Written "8".
     Eight,
+    /// Written "9".
     Nine,
+    /// Written "T" or "10".
     Ten,
+    /// Written "J".
     Jack,
+    /// Written "Q".
     Queen,
+    /// Written "K".
     King,
+    /// Written "A", high except in wheels and lowball.
     Ace,
 }
 
+/// A playing card, written like "Ah" or "A ♥".
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Card {
+    /// Rank of the card.
     pub rank: Rank,
+    /// Suit of the card.
     pub suit: Suit,
 }
 
-#[derive(Debug, Clone, Copy, EnumIter, PartialEq, Eq, PartialOrd, Ord)]
+/// A made hand: the cards forming the category followed by its kickers.
+/// Hands are ordered by category, then by rank, under the standard rules,
+/// suits are never compared. Short deck hands rank through
+/// `Hand::strength_with`.
+#[derive(Debug, Clone, Copy, EnumIter)]
 pub enum Hand {
-    HighCard(Card),
-    Pair([Card; 2]),
-    TwoPair([Card; 2], [Card; 2]),
-    ThreeOfAKind([Card; 3]),
+    /// Highest card and four kickers.
+    HighCard(Card, [Card; 4]),
+    /// The pair and three kickers.
+    Pair([Card; 2], [Card; 3]),
+    /// Higher pair, lower pair and a kicker.
+    TwoPair([Card; 2], [Card; 2], Card),
+    /// The trips and two kickers.
+    ThreeOfAKind([Card; 3], [Card; 2]),
+    /// Five cards in sequence, lowest first, the wheel with its ace first.
     Straight([Card; 5]),
+    /// Five cards of a suit, lowest first.
     Flush([Card; 5]),
+    /// The trips and the pair.
     FullHouse([Card; 3], [Card; 2]),
-    FourOfAKind([Card; 4]),
+    /// The quads and a kicker.
+    FourOfAKind([Card; 4], Card),
+    /// Straight of one suit, lowest first.
     StraightFlush([Card; 5]),
+    /// Ace-high straight flush, lowest first.
     RoyalFlush([Card; 5]),
 }
 
-#[derive(Debug, Clone)]
+/// Cards in order, dealt from the top.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
 pub struct Deck {
+    /// The cards, the top one first.
     pub cards: Vec<Card>,
 }
 
+/// Displays a deck as a grid of `rows` rows.
 pub struct DeckFormatter<'a> {
+    /// Deck to display.
     pub deck: &'a Deck,
+    /// Number of rows the cards are spread over.
     pub rows: &'a usize,
 }
+
+/// Hand strength of every player at showdown and the indices of those who
+/// won. More than one winner means the pot is split between them. In hi-lo
+/// games `low_winners` holds the best qualifying lows, which take half the
+/// pot when there are any.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Showdown {
+    /// Strength of every player's hand.
+    pub strengths: Vec<Strength>,
+    /// Players with the best hand.
+    pub winners: Vec<usize>,
+    /// Qualifying low of every player, empty unless the game is hi-lo.
+    pub lows: Vec<Option<Low>>,
+    /// Players with the best low, empty when no low qualifies.
+    pub low_winners: Vec<usize>,
+}
+
+/// Ace-to-five low of five different ranks, eight or better. A greater
+/// `Low` is a better low.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+// The ranks as a mask with the ace lowest, inverted so higher is better
+pub struct Low(pub(crate) u32);
+
+/// How a player fared in split pots: how often they took the whole pot,
+/// part of the high half only or part of the low half only, and for each
+/// half how often they won it alone or shared it. Halves are counted on
+/// their own, so a scoop is also a high half won alone, and the high half
+/// takes the whole pot when no low qualifies.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Split {
+    /// Showdowns where the player took the whole pot alone.
+    pub scoops: usize,
+    /// Showdowns won in the high half without any of the low half.
+    pub high_only: usize,
+    /// Showdowns won in the low half without any of the high half.
+    pub low_only: usize,
+    /// High halves won alone.
+    pub high_alone: usize,
+    /// High halves tied with another player.
+    pub high_shared: usize,
+    /// Low halves won alone.
+    pub low_alone: usize,
+    /// Low halves tied with another player.
+    pub low_shared: usize,
+}
+
+/// Win, tie and loss counts of one player (or hand) over many showdowns.
+/// `equity` accumulates the pot share won, so a two-way chop adds 0.5,
+/// `equity_squares` the squared shares for its standard error.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tally {
+    /// Showdowns where the whole pot was won.
+    pub wins: usize,
+    /// Showdowns where part of the pot was won.
+    pub ties: usize,
+    /// Showdowns where nothing was won.
+    pub losses: usize,
+    /// Sum of the pot shares won.
+    pub equity: f64,
+    /// Sum of the squared pot shares won.
+    pub equity_squares: f64,
+}
+
+/// Probability estimated from samples, with its standard error.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Estimate {
+    /// Estimated probability.
+    pub value: f64,
+    /// Standard error of `value`, zero when it is exact.
+    pub standard_error: f64,
+}
+
+/// Set of cards without order, cheap to copy and combine with `|`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+// A bitmask of 16 bits per suit and one bit per rank
+pub struct CardSet(pub(crate) u64);
+
+/// Value of the best five cards of a set, compared under the rules it was
+/// evaluated with. Higher is better, except for lowball hands, where lower
+/// wins. Read back with `category` and `ranks`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+// Category in bits 20..24 and the five deciding ranks below it, four bits
+// each. Bits 24..28 rank the category under the rules, bit 28 marks aces
+// counted low in lowball.
+pub struct Strength(pub(crate) u32);
+
+/// Hand rankings a set of cards is evaluated under.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rules {
+    /// Full 52 card deck and the usual hand rankings.
+    #[default]
+    Standard,
+    /// Six plus hold'em, dealt from `Deck::russian()`: A-6-7-8-9 is the
+    /// lowest straight and a flush beats a full house.
+    ShortDeck {
+        /// Three of a kind beats a straight as well.
+        trips_beat_straight: bool,
+    },
+}
+
+/// Lowball rankings, where the lowest hand wins.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lowball {
+    /// Aces are low and straights and flushes don't count, 5-4-3-2-A is
+    /// the best hand.
+    #[default]
+    AceToFive,
+    /// Aces are high and straights and flushes count, 7-5-4-3-2 is the
+    /// best hand.
+    DeuceToSeven,
+}
+
+/// Variant being played: how many hole cards are dealt and how they combine
+/// with the board.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Game {
+    /// Two hole cards and the best five of them and the board.
+    #[default]
+    Holdem,
+    /// The best hand uses exactly two of four to six hole cards and exactly
+    /// three cards of the board. In hi-lo the pot is split with the best
+    /// eight-or-better low, made under the same rule.
+    Omaha {
+        /// Hole cards dealt to every player, four to six.
+        hole_cards: usize,
+        /// Whether the pot is split with the best low.
+        hi_lo: bool,
+    },
+    /// Four cards each and no board, the best badugi wins.
+    Badugi,
+    /// Seven-card stud, the best five of seven cards dealt to every player
+    /// over five streets, so up to seven players. In hi-lo the pot is split
+    /// with the best eight-or-better low.
+    Stud {
+        /// Whether the pot is split with the best low.
+        hi_lo: bool,
+    },
+}
+
+/// Whether a card is dealt for everyone to see.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Face {
+    /// Seen only by the player it is dealt to.
+    #[default]
+    Down,
+    /// Seen by everyone.
+    Up,
+}
+
+/// Cards of one stud player by how they were dealt. The down cards of an
+/// opponent are usually unknown and left out.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct StudHand {
+    /// Cards dealt face down.
+    pub down: Deck,
+    /// Cards dealt face up.
+    pub up: Deck,
+}
+
+/// Best badugi of a hand: cards of different ranks and suits, with aces
+/// low. More cards win, then the lower top card. Higher is better.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+// The card count above bit 13 and the inverted rank mask below
+pub struct Badugi(pub(crate) u32);
+
+/// Precomputed strengths for every flush suit pattern and every multiset of
+/// five to seven ranks, so evaluation is two table reads.
+#[cfg(feature = "lookup")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupTable {
+    /// Strength of the best flush of every mask of ranks in one suit.
+    pub flushes: Vec<u32>,
+    /// Strength of every rank multiset, indexed by its perfect hash.
+    pub ranks: Vec<u32>,
+}
+
+/// Settings for `Deck::equity`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct EquityConfig {
+    /// Most runouts enumerated exactly, larger spots are sampled instead.
+    pub max_runouts: usize,
+    /// Runouts dealt when sampling.
+    pub samples: usize,
+    /// Seed for sampling, a random one is picked and reported when unset.
+    pub seed: Option<u64>,
+    /// Stop sampling before `samples` once no equity has a standard error
+    /// above this.
+    pub precision: Option<f64>,
+    /// Stop sampling before `samples` once this much time has passed.
+    pub time_budget: Option<Duration>,
+    /// Rankings to use, the deck is the one the rules are played with.
+    pub rules: Rules,
+    /// Game the hands are played in.
+    pub game: Game,
+}
+
+/// Outcome of an equity calculation, one tally per player in input order.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Equity {
+    /// Tally of every player.
+    pub players: Vec<Tally>,
+    /// Split pot results of every player, empty unless the game is hi-lo.
+    pub splits: Vec<Split>,
+    /// Runouts enumerated or sampled.
+    pub runouts: usize,
+    /// Whether every runout was enumerated.
+    pub exact: bool,
+    /// Seed that reproduces the sample, unused when exact.
+    pub seed: u64,
+}
+
+/// Two hole cards, higher card first, and how often they are played.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Combo {
+    /// Hole cards, higher first.
+    pub cards: [Card; 2],
+    /// How often the combo is played, from 0 to 1.
+    pub weight: f64,
+}
+
+/// Weighted starting hands written like "22+, A2s+, KTo+, AKs:0.5".
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Range {
+    /// Combos of the range, each at most once.
+    pub combos: Vec<Combo>,
+}
+
+/// Equity of ranges against each other: a tally per player and, for every
+/// player, a tally per combo of their range that was dealt.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct RangeEquity {
+    /// Tally of every player over all their combos.
+    pub players: Vec<Tally>,
+    /// Tally of every combo dealt to each player.
+    pub combos: Vec<Vec<(Combo, Tally)>>,
+    /// Whether every deal was enumerated.
+    pub exact: bool,
+    /// Seed that reproduces the sample, unused when exact.
+    pub seed: u64,
+}
+
+/// Settings of a Monte Carlo hold'em simulation with random hands.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationConfig {
+    /// Players dealt in every hand.
+    pub players: usize,
+    /// Most hands dealt.
+    pub iterations: usize,
+    /// Seed of the deal, a random one is picked and reported when unset.
+    pub seed: Option<u64>,
+    /// Worker threads, zero uses every available core.
+    pub threads: usize,
+    /// Iterations per batch, each batch draws from its own seeded stream.
+    pub batch: usize,
+    /// Stop before `iterations` once no standard error is above this.
+    pub precision: Option<f64>,
+    /// Stop before `iterations` once this much time has passed.
+    pub time_budget: Option<Duration>,
+    /// Rankings to use, the deck is the one the rules are played with.
+    pub rules: Rules,
+    /// Game the hands are played in.
+    pub game: Game,
+}
+
+/// Tallies of a simulation for every hand category of the game, indexed
+/// like `usize::from(Hand)`, with the seed that reproduces them. `iterations`
+/// is the number actually run, which is lower if it stopped early.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// Players dealt in every hand.
+    pub players: usize,
+    /// Hands dealt.
+    pub iterations: usize,
+    /// Seed that reproduces the deals.
+    pub seed: u64,
+    /// Game the hands were played in.
+    pub game: Game,
+    /// Tally of every hand category.
+    pub hands: Vec<Tally>,
+    /// Time the simulation took.
+    pub elapsed: Duration,
+}
+
+/// Card that improves a hand to a higher category on the next street, made
+/// with the hole cards rather than by the board alone. Clean unless it also
+/// lifts an opponent's hand to one at least as strong.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Out {
+    /// The card that improves.
+    pub card: Card,
+    /// Best hand made with it.
+    pub hand: Hand,
+    /// Whether it helps no opponent to a hand as strong.
+    pub clean: bool,
+}
+
+/// Outs of a hand on the flop or turn, with the exact chance of improving
+/// by the river over every runout.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Outs {
+    /// Best hand before the next card.
+    pub hand: Hand,
+    /// Outs on the next street, best hand made first.
+    pub outs: Vec<Out>,
+    /// Cards that can still come, not held, on the board or known dead.
+    pub unseen: usize,
+    /// Chance of improving by the river.
+    pub by_river: f64,
+    /// Chance of improving by the river without helping an opponent.
+    pub clean_by_river: f64,
+    /// Part of `by_river` that needs both the turn and the river.
+    pub runner_runner: f64,
+}
+
+/// Bet of `to_call` into a `pot` that already holds it, with the effective
+/// stack of the smaller player behind before calling, if known.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Call {
+    /// Pot including the bet.
+    pub pot: f64,
+    /// Bet to call.
+    pub to_call: f64,
+    /// Effective stack before calling.
+    pub stack: Option<f64>,
+}
+
+/// Whether calling pays with a given chance to win the pot.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallOdds {
+    /// Chance of winning the pot the odds were computed with.
+    pub equity: f64,
+    /// Equity at which calling neither wins nor loses on average.
+    pub break_even: f64,
+    /// Average winnings of calling, counting only the current pot.
+    pub ev: f64,
+    /// Winnings needed on later streets when hitting to make the call pay.
+    pub implied: f64,
+    /// Direct odds pay, or the stack left after calling covers `implied`.
+    pub profitable: bool,
+}
+
+/// How a `Table` is written out.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    #[default]
+    Text,
+    /// An object with the fields and an array of rows.
+    Json,
+    /// A header row and one line per row, fields as the first columns.
+    Csv,
+}
+
+/// Cell or field of a `Table`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Text, like a hand.
+    Text(String),
+    /// Count, like runouts.
+    Integer(u64),
+    /// Amount, like chips.
+    Number(f64),
+    /// Flag, like whether a result is exact.
+    Bool(bool),
+    /// Probability with its standard error, zero when it is exact.
+    Probability(Estimate),
+}
+
+/// Report as named fields, like the seed, and rows of named columns, so
+/// it renders the same in every `Format`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    /// Named values about the whole report.
+    pub fields: Vec<(String, Value)>,
+    /// Column names.
+    pub columns: Vec<String>,
+    /// Rows, one value per column.
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Invalid input to dealing, parsing or hand evaluation.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PokerError {
+    /// Hold'em hand without exactly two cards.
+    HandSize(usize),
+    /// Board of a best hand without three to five cards.
+    TableSize(usize),
+    /// Board of an equity calculation with more than five cards.
+    BoardSize(usize),
+    /// Simulation without players.
+    NoPlayers,
+    /// Range, by player index, left without combos by the known cards.
+    EmptyRange(usize),
+    /// Ranges that can't all be dealt at once without sharing a card.
+    RangesConflict,
+    /// Outs asked for on a board that is not a flop or a turn.
+    OutsBoard(usize),
+    /// Omaha hand without four to six cards.
+    OmahaHandSize(usize),
+    /// Lowball hand without five to seven cards.
+    LowballHandSize(usize),
+    /// Badugi hand without exactly four cards.
+    BadugiHandSize(usize),
+    /// Equity of a game without a board, like badugi.
+    NoBoard,
+    /// Stud hand of more than seven cards.
+    StudHandSize(usize),
+    /// Seven-card stud street other than third to seventh.
+    StudStreet(usize),
+    /// Game a calculation does not support, like ranges outside hold'em.
+    UnsupportedGame(Game),
+    /// Deal of more cards than the deck has left.
+    NotEnoughCards {
+        /// Cards the deal needs.
+        requested: usize,
+        /// Cards in the deck.
+        left: usize,
+    },
+    /// Card given more than once.
+    DuplicateCard(Card),
+    /// Character at `position` (counted in chars) is not a rank.
+    UnknownRank {
+        /// Text being parsed.
+        input: String,
+        /// Position of the character.
+        position: usize,
+    },
+    /// Character at `position` (counted in chars) is not a suit.
+    UnknownSuit {
+        /// Text being parsed.
+        input: String,
+        /// Position of the character.
+        position: usize,
+    },
+    /// Rank at the end of the input without a suit after it.
+    MissingSuit {
+        /// Text being parsed.
+        input: String,
+    },
+    /// Not a hand name followed by its cards, or cards that don't make it.
+    InvalidHand(String),
+    /// Text with the wrong number of cards.
+    CardCount {
+        /// Text being parsed.
+        input: String,
+        /// Cards it should hold.
+        expected: usize,
+        /// Cards it holds.
+        found: usize,
+    },
+    /// Pot odds asked for with an equity outside 0 to 1, or a pot or bet
+    /// that is not positive.
+    InvalidOdds(String),
+    /// Lookup table cache that can't be read or written, with the reason.
+    LookupFile(String),
+}
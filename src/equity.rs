@@ -0,0 +1,356 @@
+use crate::data::{
+    CardSet, Combo, Deck, Equity, EquityConfig, Game, PokerError, Range, RangeEquity, Rules,
+    Showdown, Split, Tally,
+};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+/// Samples dealt between two checks of an adaptive equity calculation.
+const CHECK_EVERY: usize = 1_000;
+
+impl Default for EquityConfig {
+    fn default() -> Self {
+        EquityConfig {
+            max_runouts: 2_000_000,
+            samples: 100_000,
+            seed: None,
+            precision: None,
+            time_budget: None,
+            rules: Rules::Standard,
+            game: Game::Holdem,
+        }
+    }
+}
+
+impl EquityConfig {
+    /// Whether sampling stops before runout `sample`: every equity in
+    /// `players` is as precise as asked for, or the time budget counted
+    /// from `start` is spent. Only checked every `CHECK_EVERY` samples.
+    pub(crate) fn stops_at(&self, sample: usize, players: &[Tally], start: Instant) -> bool {
+        if sample == 0 || !sample.is_multiple_of(CHECK_EVERY) {
+            return false;
+        }
+        let precise = self.precision.is_some_and(|precision| {
+            players
+                .iter()
+                .all(|tally| tally.equity_estimate().standard_error <= precision)
+        });
+        let out_of_time = self
+            .time_budget
+            .is_some_and(|budget| start.elapsed() >= budget);
+        precise || out_of_time
+    }
+}
+
+/// Counts the pot share and split pot results of every player.
+pub(crate) fn record(showdown: &Showdown, players: &mut [Tally], splits: &mut [Split]) {
+    for (player, tally) in players.iter_mut().enumerate() {
+        tally.record(showdown.share(player));
+    }
+    for (player, split) in splits.iter_mut().enumerate() {
+        split.record(showdown, player);
+    }
+}
+
+impl Deck {
+    /// Equity of every hand on `board`, with `dead` cards removed from the
+    /// deck. Enumerates every runout, or samples them when there are more
+    /// than `config.max_runouts`, stopping early once precise enough or out
+    /// of time.
+    pub fn equity(hands: &[Deck], board: &Deck, dead: &Deck, config: &EquityConfig) -> Equity {
+        Self::try_equity(hands, board, dead, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `equity`, but fails on a board over five cards or repeated cards.
+    pub fn try_equity(
+        hands: &[Deck],
+        board: &Deck,
+        dead: &Deck,
+        config: &EquityConfig,
+    ) -> Result<Equity, PokerError> {
+        if board.cards.len() > 5 {
+            return Err(PokerError::BoardSize(board.cards.len()));
+        }
+
+        if !config.game.has_board() {
+            return Err(PokerError::NoBoard);
+        }
+        for hand in hands {
+            config.game.check_hand(hand)?;
+        }
+        Self::check_unique(hands.iter().chain([board, dead]))?;
+        let known_set: CardSet = hands
+            .iter()
+            .chain([board, dead])
+            .flat_map(|deck| deck.cards.iter().copied())
+            .collect();
+
+        let mut deck = config.rules.deck();
+        deck.cards.retain(|card| !known_set.contains(*card));
+
+        let missing = 5 - board.cards.len();
+        if missing > deck.cards.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested: missing,
+                left: deck.cards.len(),
+            });
+        }
+        let runouts = binomial(deck.cards.len(), missing);
+        let exact = runouts <= config.max_runouts;
+        let seed = config.seed.unwrap_or_else(rand::random);
+
+        let board_set = CardSet::from(board);
+        let hand_sets: Vec<_> = hands.iter().map(CardSet::from).collect();
+        let mut players = vec![Tally::default(); hands.len()];
+        let hi_lo = config.game.is_hi_lo();
+        let mut splits = vec![Split::default(); if hi_lo { hands.len() } else { 0 }];
+
+        let showdown = |runout: CardSet| {
+            let table = board_set | runout;
+            let strengths = hand_sets
+                .iter()
+                .map(|hand| config.game.evaluate(*hand, table, config.rules))
+                .collect();
+            match hi_lo {
+                true => {
+                    let lows = hand_sets.iter().map(|hand| config.game.low(*hand, table));
+                    Showdown::hi_lo(strengths, lows.collect())
+                }
+                false => Showdown::new(strengths),
+            }
+        };
+
+        let mut done = 0;
+        if exact {
+            for runout in deck.cards.iter().copied().combinations(missing) {
+                record(
+                    &showdown(runout.into_iter().collect()),
+                    &mut players,
+                    &mut splits,
+                );
+            }
+            done = runouts;
+        } else {
+            use rand::seq::SliceRandom;
+            let mut rng = StdRng::seed_from_u64(seed);
+            let start = Instant::now();
+            while done < config.samples && !config.stops_at(done, &players, start) {
+                let (runout, _) = deck.cards.partial_shuffle(&mut rng, missing);
+                let showdown = showdown(runout.iter().copied().collect());
+                record(&showdown, &mut players, &mut splits);
+                done += 1;
+            }
+        }
+
+        Ok(Equity {
+            players,
+            splits,
+            runouts: done,
+            exact,
+            seed,
+        })
+    }
+}
+
+impl Range {
+    /// Equity of every range against the others. Combos are dealt in
+    /// proportion to their weight, deals where two players hold the same
+    /// card are thrown away. Exact when every range is a single hand,
+    /// otherwise sampled and stopped early like `Deck::equity`.
+    pub fn equity(
+        ranges: &[Range],
+        board: &Deck,
+        dead: &Deck,
+        config: &EquityConfig,
+    ) -> RangeEquity {
+        Self::try_equity(ranges, board, dead, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `equity`, but fails instead of panicking on invalid input.
+    pub fn try_equity(
+        ranges: &[Range],
+        board: &Deck,
+        dead: &Deck,
+        config: &EquityConfig,
+    ) -> Result<RangeEquity, PokerError> {
+        if board.cards.len() > 5 {
+            return Err(PokerError::BoardSize(board.cards.len()));
+        }
+        Deck::check_unique([board, dead])?;
+        if !config.game.has_board() {
+            return Err(PokerError::NoBoard);
+        }
+        // Combos are hold'em hands, Omaha equity goes through `Deck::equity`
+        if config.game != Game::Holdem {
+            return Err(PokerError::UnsupportedGame(config.game));
+        }
+
+        let known = CardSet::from(board) | CardSet::from(dead);
+        let dealt = CardSet::from(&config.rules.deck());
+        let ranges: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                let mut range = range.clone();
+                range.combos.retain(|combo| {
+                    combo.weight > 0.0
+                        && combo
+                            .cards
+                            .iter()
+                            .all(|card| dealt.contains(*card) && !known.contains(*card))
+                });
+                range
+            })
+            .collect();
+
+        if let Some(player) = ranges.iter().position(Range::is_empty) {
+            return Err(PokerError::EmptyRange(player));
+        }
+
+        if ranges.iter().all(|range| range.len() == 1) {
+            let hands: Vec<_> = ranges
+                .iter()
+                .map(|range| Deck::from(range.combos[0].cards.to_vec()))
+                .collect();
+            let equity = Deck::try_equity(&hands, board, dead, config)?;
+            let combos = ranges
+                .iter()
+                .zip(&equity.players)
+                .map(|(range, tally)| vec![(range.combos[0], *tally)])
+                .collect();
+            return Ok(RangeEquity {
+                players: equity.players,
+                combos,
+                exact: equity.exact,
+                seed: equity.seed,
+            });
+        }
+
+        if !Self::can_deal(&ranges, CardSet::default()) {
+            return Err(PokerError::RangesConflict);
+        }
+
+        let mut deck = config.rules.deck();
+        deck.cards.retain(|card| !known.contains(*card));
+        let missing = 5 - board.cards.len();
+        let requested = 2 * ranges.len() + missing;
+        if requested > deck.cards.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested,
+                left: deck.cards.len(),
+            });
+        }
+        let board_set = CardSet::from(board);
+
+        let mut players = vec![Tally::default(); ranges.len()];
+        let mut combos: Vec<Vec<(Combo, Tally)>> = ranges
+            .iter()
+            .map(|range| {
+                range
+                    .combos
+                    .iter()
+                    .map(|combo| (*combo, Tally::default()))
+                    .collect()
+            })
+            .collect();
+
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut dealt = vec![0; ranges.len()];
+        let start = Instant::now();
+
+        for sample in 0..config.samples {
+            if config.stops_at(sample, &players, start) {
+                break;
+            }
+            let used = Self::deal_combos(&ranges, &mut dealt, &mut rng)
+                .ok_or(PokerError::RangesConflict)?;
+
+            let mut table = board_set;
+            while table.len() < board_set.len() + missing {
+                let card = deck.cards[rng.gen_range(0..deck.cards.len())];
+                if !used.contains(card) {
+                    table.insert(card);
+                }
+            }
+
+            let strengths = ranges
+                .iter()
+                .zip(&dealt)
+                .map(|(range, &i)| {
+                    let hand: CardSet = range.combos[i].cards.into_iter().collect();
+                    (table | hand).evaluate_with(config.rules)
+                })
+                .collect();
+            let showdown = Showdown::new(strengths);
+
+            for (player, &i) in dealt.iter().enumerate() {
+                let share = showdown.share(player);
+                players[player].record(share);
+                combos[player][i].1.record(share);
+            }
+        }
+
+        for combos in combos.iter_mut() {
+            combos.retain(|(_, tally)| tally.games() > 0);
+        }
+
+        Ok(RangeEquity {
+            players,
+            combos,
+            exact: false,
+            seed,
+        })
+    }
+
+    /// Whether every range can hold a combo without sharing a card with
+    /// the others, given the cards already `used`.
+    fn can_deal(ranges: &[Range], used: CardSet) -> bool {
+        let Some((range, rest)) = ranges.split_first() else {
+            return true;
+        };
+        range.combos.iter().any(|combo| {
+            let cards: CardSet = combo.cards.into_iter().collect();
+            used.is_disjoint(cards) && Self::can_deal(rest, used | cards)
+        })
+    }
+
+    /// Picks one combo per range by weight, redealing on card conflicts.
+    /// Returns the cards held by the players, or `None` when no deal came
+    /// up without conflict.
+    fn deal_combos<R: Rng>(ranges: &[Range], dealt: &mut [usize], rng: &mut R) -> Option<CardSet> {
+        for _ in 0..10_000 {
+            let mut used = CardSet::default();
+            let conflict = ranges.iter().zip(dealt.iter_mut()).any(|(range, dealt)| {
+                let mut pick = rng.gen::<f64>() * range.total_weight();
+                *dealt = range
+                    .combos
+                    .iter()
+                    .position(|combo| {
+                        pick -= combo.weight;
+                        pick < 0.0
+                    })
+                    .unwrap_or(range.len() - 1);
+
+                let cards: CardSet = range.combos[*dealt].cards.into_iter().collect();
+                let conflict = !used.is_disjoint(cards);
+                used = used | cards;
+                conflict
+            });
+
+            if !conflict {
+                return Some(used);
+            }
+        }
+
+        None
+    }
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
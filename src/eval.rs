@@ -0,0 +1,365 @@
+use crate::data::{Card, CardSet, Deck, Hand, Rank, Rules, Strength, Suit};
+use std::ops::BitOr;
+
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+const RANK_BITS: u16 = 0x1FFF;
+const WHEEL: u16 = 0b1_0000_0000_1111;
+const SHORT_WHEEL: u16 = 0b1_0000_1111_0000;
+
+/// Flag of lowball strengths whose ranks count the ace below the deuce.
+pub(crate) const ACE_LOW: u32 = 1 << 28;
+
+impl Card {
+    /// The bit of this card in a `CardSet`.
+    pub fn mask(&self) -> u64 {
+        1 << (self.suit as u64 * 16 + self.rank as u64)
+    }
+}
+
+impl CardSet {
+    /// Adds `card`, if it is not in the set yet.
+    pub fn insert(&mut self, card: Card) {
+        self.0 |= card.mask();
+    }
+
+    /// Takes `card` out, if it is in the set.
+    pub fn remove(&mut self, card: Card) {
+        self.0 &= !card.mask();
+    }
+
+    /// Whether `card` is in the set.
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & card.mask() != 0
+    }
+
+    /// Number of cards in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Whether the set has no card.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether the sets share no card.
+    pub fn is_disjoint(&self, other: CardSet) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Cards in the set, ordered like `Deck::sort`.
+    pub fn cards(&self) -> impl Iterator<Item = Card> + '_ {
+        itertools::iproduct!(RANKS, SUITS)
+            .map(|(rank, suit)| Card { rank, suit })
+            .filter(|card| self.contains(*card))
+    }
+
+    fn suit_ranks(&self, suit: usize) -> u16 {
+        (self.0 >> (suit * 16)) as u16 & RANK_BITS
+    }
+
+    fn flush_suit(&self) -> Option<usize> {
+        (0..4).find(|&suit| self.suit_ranks(suit).count_ones() >= 5)
+    }
+
+    /// Strength of the best five cards, without allocating.
+    pub fn evaluate(&self) -> Strength {
+        self.evaluate_with(Rules::Standard)
+    }
+
+    /// Strength of the best five cards under `rules`.
+    #[inline]
+    pub fn evaluate_with(&self, rules: Rules) -> Strength {
+        let [s, h, d, c] = [0, 1, 2, 3].map(|suit| self.suit_ranks(suit));
+
+        let any = s | h | d | c;
+        let two_plus = (s & h) | (s & d) | (s & c) | (h & d) | (h & c) | (d & c);
+        let three_plus = (s & h & d) | (s & h & c) | (s & d & c) | (h & d & c);
+        let quads = s & h & d & c;
+
+        let flush = self.flush_suit().map(|suit| self.suit_ranks(suit));
+        let strength = |category, ranks| Strength::new(category, ranks).under(rules);
+        let short_deck = matches!(rules, Rules::ShortDeck { .. });
+        let trips_beat_straight = matches!(
+            rules,
+            Rules::ShortDeck {
+                trips_beat_straight: true
+            }
+        );
+
+        if let Some(ranks) = flush.and_then(|flush| straight(flush, rules)) {
+            let category = if ranks[0] == Rank::Ace as u8 { 9 } else { 8 };
+            return strength(category, ranks);
+        }
+
+        if quads != 0 {
+            let q = highest(quads);
+            let [k] = top_ranks(any & !bit(q));
+            return strength(7, [q, q, q, q, k]);
+        }
+
+        if let Some(flush) = flush.filter(|_| short_deck) {
+            return strength(5, top_ranks(flush));
+        }
+
+        if three_plus != 0 {
+            let t = highest(three_plus);
+            let rest = two_plus & !bit(t);
+            if rest != 0 {
+                let p = highest(rest);
+                return strength(6, [t, t, t, p, p]);
+            }
+        }
+
+        if let Some(flush) = flush {
+            return strength(5, top_ranks(flush));
+        }
+
+        let trips = (three_plus != 0).then(|| {
+            let t = highest(three_plus);
+            let [k1, k2] = top_ranks(any & !bit(t));
+            strength(3, [t, t, t, k1, k2])
+        });
+
+        if let Some(trips) = trips.filter(|_| trips_beat_straight) {
+            return trips;
+        }
+
+        if let Some(ranks) = straight(any, rules) {
+            return strength(4, ranks);
+        }
+
+        if let Some(trips) = trips {
+            return trips;
+        }
+
+        if two_plus.count_ones() >= 2 {
+            let [p1, p2] = top_ranks(two_plus);
+            let [k] = top_ranks(any & !bit(p1) & !bit(p2));
+            return strength(2, [p1, p1, p2, p2, k]);
+        }
+
+        if two_plus != 0 {
+            let p = highest(two_plus);
+            let [k1, k2, k3] = top_ranks(any & !bit(p));
+            return strength(1, [p, p, k1, k2, k3]);
+        }
+
+        strength(0, top_ranks(any))
+    }
+
+    /// Best hand in the set, rebuilt from its strength.
+    pub fn best_hand(&self) -> Hand {
+        self.best_hand_with(Rules::Standard)
+    }
+
+    /// Best hand in the set under `rules`. Panics on fewer than five cards,
+    /// which make no `Hand`.
+    pub fn best_hand_with(&self, rules: Rules) -> Hand {
+        use Hand::*;
+        assert!(
+            self.len() >= 5,
+            "A hand needs five cards, got {}",
+            self.len()
+        );
+
+        let strength = self.evaluate_with(rules);
+        let [r0, r1, r2, r3, r4] = strength.ranks();
+
+        match strength.category() {
+            0 => HighCard(
+                self.take::<1>(r0)[0],
+                [r1, r2, r3, r4].map(|rank| self.take::<1>(rank)[0]),
+            ),
+            1 => Pair(
+                self.take(r0),
+                [r2, r3, r4].map(|rank| self.take::<1>(rank)[0]),
+            ),
+            2 => TwoPair(self.take(r0), self.take(r2), self.take::<1>(r4)[0]),
+            3 => ThreeOfAKind(self.take(r0), [r3, r4].map(|rank| self.take::<1>(rank)[0])),
+            4 => Straight([r4, r3, r2, r1, r0].map(|rank| self.take::<1>(rank)[0])),
+            5 => Flush(self.suited([r4, r3, r2, r1, r0])),
+            6 => FullHouse(self.take(r0), self.take(r3)),
+            7 => FourOfAKind(self.take(r0), self.take::<1>(r4)[0]),
+            8 => StraightFlush(self.suited([r4, r3, r2, r1, r0])),
+            9 => RoyalFlush(self.suited([r4, r3, r2, r1, r0])),
+            _ => unreachable!(),
+        }
+    }
+
+    /// `N` cards of `rank`, highest suits preferred, in deck order.
+    fn take<const N: usize>(&self, rank: Rank) -> [Card; N] {
+        let mut cards = [Card::default(); N];
+        let mut left = N;
+        for suit in SUITS.into_iter().rev() {
+            let card = Card { rank, suit };
+            if left > 0 && self.contains(card) {
+                left -= 1;
+                cards[left] = card;
+            }
+        }
+        cards
+    }
+
+    fn suited(&self, ranks: [Rank; 5]) -> [Card; 5] {
+        let suit = SUITS[self.flush_suit().unwrap_or_default()];
+        ranks.map(|rank| Card { rank, suit })
+    }
+}
+
+impl Strength {
+    pub(crate) fn new(category: u32, ranks: [u8; 5]) -> Self {
+        let ranks = ranks.iter().fold(0, |acc, &rank| (acc << 4) | rank as u32);
+        Strength((category << 24) | (category << 20) | ranks)
+    }
+
+    /// Same hand ranked among the categories as `rules` order them.
+    fn under(self, rules: Rules) -> Self {
+        let order = rules.order(self.category()) as u32;
+        Strength((self.0 & 0xFF_FFFF) | (order << 24))
+    }
+
+    /// Index of the hand category, as in `usize::from(Hand)`.
+    pub fn category(&self) -> usize {
+        (self.0 >> 20) as usize & 0xF
+    }
+
+    /// The five deciding ranks, most significant first.
+    pub fn ranks(&self) -> [Rank; 5] {
+        let ace_low = self.0 & ACE_LOW != 0;
+        [16, 12, 8, 4, 0].map(|shift| {
+            let rank = (self.0 >> shift) as usize & 0xF;
+            match ace_low {
+                true => RANKS[(rank + 12) % 13],
+                false => RANKS[rank],
+            }
+        })
+    }
+}
+
+impl Rules {
+    /// Rank of hand category `category` among the others, zero is lowest.
+    pub fn order(&self, category: usize) -> usize {
+        match (self, category) {
+            (Rules::Standard, _) => category,
+            (Rules::ShortDeck { .. }, 5) => 6,
+            (Rules::ShortDeck { .. }, 6) => 5,
+            (
+                Rules::ShortDeck {
+                    trips_beat_straight: true,
+                },
+                3,
+            ) => 4,
+            (
+                Rules::ShortDeck {
+                    trips_beat_straight: true,
+                },
+                4,
+            ) => 3,
+            (Rules::ShortDeck { .. }, _) => category,
+        }
+    }
+
+    /// Deck the game is dealt from.
+    pub fn deck(&self) -> Deck {
+        match self {
+            Rules::Standard => Deck::french(),
+            Rules::ShortDeck { .. } => Deck::russian(),
+        }
+    }
+}
+
+impl Hand {
+    /// Packed value of the hand, comparable to `CardSet::evaluate`.
+    pub fn strength(&self) -> Strength {
+        self.strength_with(Rules::Standard)
+    }
+
+    /// Packed value of the hand under `rules`, comparable to
+    /// `CardSet::evaluate_with`.
+    pub fn strength_with(&self, rules: Rules) -> Strength {
+        let ranks = self.ranks().map(|rank| rank as u8);
+        Strength::new(usize::from(*self) as u32, ranks).under(rules)
+    }
+}
+
+impl From<&Deck> for CardSet {
+    fn from(deck: &Deck) -> Self {
+        deck.cards.iter().copied().collect()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        CardSet(cards.into_iter().fold(0, |mask, card| mask | card.mask()))
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, other: Self) -> Self {
+        CardSet(self.0 | other.0)
+    }
+}
+
+fn bit(rank: u8) -> u16 {
+    1 << rank
+}
+
+fn highest(ranks: u16) -> u8 {
+    15 - ranks.leading_zeros() as u8
+}
+
+/// The `N` highest ranks in the mask, best first.
+fn top_ranks<const N: usize>(mut ranks: u16) -> [u8; N] {
+    let mut top = [0; N];
+    for rank in top.iter_mut() {
+        if ranks == 0 {
+            break;
+        }
+        *rank = highest(ranks);
+        ranks &= !bit(*rank);
+    }
+    top
+}
+
+/// Ranks of the highest straight in the mask, from the top card down. The
+/// wheel, A-2-3-4-5 or A-6-7-8-9 in a short deck, ends with its ace.
+fn straight(ranks: u16, rules: Rules) -> Option<[u8; 5]> {
+    let (wheel, wheel_top) = match rules {
+        Rules::Standard => (WHEEL, Rank::Five as u8),
+        Rules::ShortDeck { .. } => (SHORT_WHEEL, Rank::Nine as u8),
+    };
+    let top = (4..13)
+        .rev()
+        .find(|&top| (ranks >> (top - 4)) & 0x1F == 0x1F);
+
+    match top {
+        Some(top) if top > wheel_top || ranks & wheel != wheel => {
+            Some([top, top - 1, top - 2, top - 3, top - 4])
+        }
+        _ if ranks & wheel == wheel => {
+            let [a, b, c, d] = top_ranks(wheel & !bit(Rank::Ace as u8));
+            Some([a, b, c, d, Rank::Ace as u8])
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,61 @@
+//! Hold'em, Omaha and seven-card stud hand evaluation, equity and Monte
+//! Carlo simulation.
+//!
+//! Cards are parsed from notation like "AhKd" or "A ♥ K ♦", combined into a
+//! [`CardSet`] and evaluated to a [`Strength`] that compares like hands do.
+//! [`Deck::equity`] and [`Range::equity`] compute how often hands or ranges
+//! win on a board, [`SimulationConfig`] runs seeded simulations of random
+//! deals on every core. Lowball hands rank with
+//! [`CardSet::evaluate_lowball`], the lowest wins, and four card hands as a
+//! [`Badugi`]. Every report renders as a
+//! [`Table`] of text, JSON or CSV.
+//!
+//! ```
+//! use poker::{Deck, EquityConfig, Hand};
+//!
+//! let table = Deck::parse("7h 8h 2c Ks 3d").unwrap();
+//! let hand = Deck::best_hand(&table, Deck::parse("AhKh").unwrap());
+//! assert!(matches!(hand, Hand::Pair(..)));
+//!
+//! let hands = [Deck::parse("AhKh").unwrap(), Deck::parse("QsQd").unwrap()];
+//! let board = Deck::parse("7h8h2c").unwrap();
+//! let equity = Deck::equity(&hands, &board, &Deck::from(vec![]), &EquityConfig::default());
+//! assert!(equity.exact);
+//! assert_eq!(equity.runouts, 990);
+//! ```
+//!
+//! Optional features: `lookup` adds [`LookupTable`], a precomputed evaluator
+//! cached on disk, `serde` makes every type serializable with cards written
+//! as "Ah".
+
+#![warn(missing_docs)]
+
+mod badugi;
+mod code;
+mod data;
+mod equity;
+mod eval;
+#[cfg(feature = "lookup")]
+mod lookup;
+mod low;
+mod odds;
+mod omaha;
+mod outs;
+mod range;
+mod report;
+#[cfg(feature = "serde")]
+mod serialize;
+mod showdown;
+mod simulation;
+mod stud;
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "lookup")]
+pub use data::LookupTable;
+pub use data::{
+    Badugi, Call, CallOdds, Card, CardSet, Combo, Deck, DeckFormatter, Equity, EquityConfig,
+    Estimate, Face, Format, Game, Hand, Low, Lowball, Out, Outs, PokerError, Range, RangeEquity,
+    Rank, Rules, Showdown, SimulationConfig, SimulationReport, Split, Strength, StudHand, Suit,
+    Table, Tally, Value,
+};
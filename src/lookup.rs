@@ -0,0 +1,195 @@
+use crate::data::{Card, CardSet, Deck, LookupTable, Rank, Strength, Suit};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+const MAGIC: &[u8; 8] = b"PKLOOKUP";
+const VERSION: u32 = 2;
+
+const MIN_CARDS: usize = 5;
+const MAX_CARDS: usize = 7;
+
+/// `WAYS[m][s]`: ways to put `s` cards into `m` ranks, at most four per rank.
+const WAYS: [[u32; MAX_CARDS + 1]; 14] = ways();
+
+const fn ways() -> [[u32; MAX_CARDS + 1]; 14] {
+    let mut ways = [[0; MAX_CARDS + 1]; 14];
+    ways[0][0] = 1;
+    let mut m = 1;
+    while m < 14 {
+        let mut s = 0;
+        while s <= MAX_CARDS {
+            let mut v = 0;
+            while v <= 4 && v <= s {
+                ways[m][s] += ways[m - 1][s - v];
+                v += 1;
+            }
+            s += 1;
+        }
+        m += 1;
+    }
+    ways
+}
+
+/// Start of the rank table section for sets of `cards` cards.
+fn offset(cards: usize) -> usize {
+    (MIN_CARDS..cards).map(|n| WAYS[13][n] as usize).sum()
+}
+
+/// Perfect hash of a rank multiset among those of the same size.
+fn rank_index(counts: &[u8; 13]) -> usize {
+    let cards: usize = counts.iter().map(|&count| count as usize).sum();
+    let mut left = cards;
+    let mut index = offset(cards);
+    for (rank, &count) in counts.iter().enumerate() {
+        for v in 0..count as usize {
+            index += WAYS[12 - rank][left - v] as usize;
+        }
+        left -= count as usize;
+    }
+    index
+}
+
+fn for_each_counts(counts: &mut [u8; 13], rank: usize, left: usize, f: &mut impl FnMut(&[u8; 13])) {
+    if rank == 13 {
+        if left == 0 {
+            f(counts);
+        }
+        return;
+    }
+    for count in 0..=left.min(4) {
+        counts[rank] = count as u8;
+        for_each_counts(counts, rank + 1, left - count, f);
+    }
+    counts[rank] = 0;
+}
+
+/// Strength found by the detector functions, the first two cards act as hand.
+fn detect(cards: Vec<Card>) -> u32 {
+    let mut table = Deck::from(cards);
+    let hand = table.deal(2);
+    Deck::all_hands(&table, hand)[0].strength().0
+}
+
+impl LookupTable {
+    /// Builds the tables by running the detectors on every pattern once.
+    pub fn build() -> Self {
+        let ranks: Vec<_> = Rank::iter().collect();
+        let suits: Vec<_> = Suit::iter().collect();
+
+        let flushes = (0..1u32 << 13)
+            .map(|mask| {
+                if !(MIN_CARDS..=MAX_CARDS).contains(&(mask.count_ones() as usize)) {
+                    return 0;
+                }
+                let cards = (0..13)
+                    .filter(|rank| mask & (1 << rank) != 0)
+                    .map(|rank| Card::new(ranks[rank], Suit::Spades))
+                    .collect();
+                detect(cards)
+            })
+            .collect();
+
+        let mut table = vec![0; offset(MAX_CARDS + 1)];
+        for cards in MIN_CARDS..=MAX_CARDS {
+            for_each_counts(&mut [0; 13], 0, cards, &mut |counts| {
+                // Rotate suits across ranks so no five cards share one
+                let mut next_suit = 0;
+                let mut hand = vec![];
+                for (rank, &count) in counts.iter().enumerate() {
+                    for _ in 0..count {
+                        hand.push(Card::new(ranks[rank], suits[next_suit % 4]));
+                        next_suit += 1;
+                    }
+                }
+                table[rank_index(counts)] = detect(hand);
+            });
+        }
+
+        LookupTable {
+            flushes,
+            ranks: table,
+        }
+    }
+
+    /// Loads the tables from `path`, building and saving them on first use.
+    pub fn load_or_build<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        match Self::load(&path) {
+            Ok(table) => Ok(table),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let table = Self::build();
+                table.save(&path)?;
+                Ok(table)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the table to `path` in the cache format.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        for section in [&self.flushes, &self.ranks] {
+            file.write_all(&(section.len() as u32).to_le_bytes())?;
+            for value in section {
+                file.write_all(&value.to_le_bytes())?;
+            }
+        }
+        file.flush()
+    }
+
+    /// Reads a table written by `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut bytes = vec![];
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+
+        let invalid = |reason| io::Error::new(io::ErrorKind::InvalidData, reason);
+
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            return Err(invalid("not a lookup table file"));
+        };
+        let mut words = rest
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]));
+
+        if words.next() != Some(VERSION) {
+            return Err(invalid("unsupported lookup table version"));
+        }
+
+        let mut section = |expected: usize| {
+            let len = words.next().ok_or(invalid("truncated lookup table"))? as usize;
+            if len != expected {
+                return Err(invalid("lookup table has the wrong size"));
+            }
+            let values: Vec<_> = words.by_ref().take(len).collect();
+            if values.len() != len {
+                return Err(invalid("truncated lookup table"));
+            }
+            Ok(values)
+        };
+
+        let flushes = section(1 << 13)?;
+        let ranks = section(offset(MAX_CARDS + 1))?;
+
+        Ok(LookupTable { flushes, ranks })
+    }
+
+    /// Strength of a set of five to seven cards.
+    pub fn evaluate(&self, set: CardSet) -> Strength {
+        let suits = [0, 16, 32, 48].map(|shift| (set.0 >> shift) as usize & 0x1FFF);
+
+        // Seven cards can't hold a flush and a full house at once
+        if let Some(&flush) = suits.iter().find(|ranks| ranks.count_ones() >= 5) {
+            return Strength(self.flushes[flush]);
+        }
+
+        let mut counts = [0; 13];
+        for (rank, count) in counts.iter_mut().enumerate() {
+            *count = suits.iter().map(|ranks| (ranks >> rank) as u8 & 1).sum();
+        }
+
+        Strength(self.ranks[rank_index(&counts)])
+    }
+}
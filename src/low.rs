@@ -0,0 +1,122 @@
+use crate::data::{CardSet, Game, Low, Lowball, Rank, Strength};
+use crate::eval::ACE_LOW;
+use itertools::Itertools;
+use strum::IntoEnumIterator;
+
+/// Ace to eight with the ace lowest, as bits of a low mask.
+const EIGHT_OR_BETTER: u32 = 0xFF;
+
+impl CardSet {
+    /// Best ace-to-five low of five different ranks, eight or better.
+    /// Straights and flushes don't count against a low.
+    pub fn low_eight(&self) -> Option<Low> {
+        let any = (0..4).fold(0, |any, suit| any | (self.0 >> (suit * 16)) as u32 & 0x1FFF);
+        let mut mask = ((any & 0x7F) << 1) | ((any >> 12) & 1);
+        if mask.count_ones() < 5 {
+            return None;
+        }
+        while mask.count_ones() > 5 {
+            mask &= !(1 << (31 - mask.leading_zeros()));
+        }
+        Some(Low(EIGHT_OR_BETTER - mask))
+    }
+
+    /// Best lowball hand of five cards of the set, as a `Strength` where the
+    /// lowest value wins. Pairs always count against a lowball hand.
+    pub fn evaluate_lowball(&self, lowball: Lowball) -> Strength {
+        self.cards()
+            .combinations(self.len().min(5))
+            .map(|five| {
+                let five: CardSet = five.into_iter().collect();
+                match lowball {
+                    Lowball::AceToFive => five.ace_to_five(),
+                    Lowball::DeuceToSeven => five.deuce_to_seven(),
+                }
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Pairs and kickers of five cards, ranked with the ace below the deuce.
+    fn ace_to_five(&self) -> Strength {
+        let mut counts = [0; 13];
+        for card in self.cards() {
+            counts[(card.rank as usize + 1) % 13] += 1;
+        }
+        let groups: Vec<(usize, u8)> = (0..13)
+            .filter(|&rank| counts[rank] > 0)
+            .map(|rank| (counts[rank], rank as u8))
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .collect();
+
+        let sizes: Vec<_> = groups.iter().map(|&(count, _)| count).collect();
+        let category = match sizes[..] {
+            [4, ..] => 7,
+            [3, 2] => 6,
+            [3, ..] => 3,
+            [2, 2, ..] => 2,
+            [2, ..] => 1,
+            _ => 0,
+        };
+        let mut ranks = [0; 5];
+        let grouped = groups
+            .iter()
+            .flat_map(|&(count, rank)| std::iter::repeat_n(rank, count));
+        for (slot, rank) in ranks.iter_mut().zip(grouped) {
+            *slot = rank;
+        }
+        Strength(Strength::new(category, ranks).0 | ACE_LOW)
+    }
+
+    /// Five cards ranked as usual, except that A-2-3-4-5 is no straight.
+    fn deuce_to_seven(&self) -> Strength {
+        let strength = self.evaluate();
+        let wheel = [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two];
+        match (strength.category(), strength.ranks()[0]) {
+            (4, Rank::Five) => Strength::new(0, wheel.map(|rank| rank as u8)),
+            (8, Rank::Five) => Strength::new(5, wheel.map(|rank| rank as u8)),
+            _ => strength,
+        }
+    }
+}
+
+impl Low {
+    /// The five ranks of the low, highest first.
+    pub fn ranks(&self) -> Vec<Rank> {
+        ace_low_ranks(EIGHT_OR_BETTER - self.0)
+    }
+}
+
+impl Game {
+    /// Whether the pot is split between the best high and the best low.
+    pub fn is_hi_lo(&self) -> bool {
+        matches!(
+            self,
+            Game::Omaha { hi_lo: true, .. } | Game::Stud { hi_lo: true }
+        )
+    }
+
+    /// Best eight-or-better low of `hole` on `board`, made with two hole
+    /// cards and three board cards in Omaha.
+    pub fn low(&self, hole: CardSet, board: CardSet) -> Option<Low> {
+        match self {
+            Game::Omaha { .. } => crate::omaha::omaha_sets(hole, board)
+                .filter_map(|set| set.low_eight())
+                .max(),
+            _ => (hole | board).low_eight(),
+        }
+    }
+}
+
+/// Ranks of a mask with the ace as bit zero, highest first.
+pub(crate) fn ace_low_ranks(mask: u32) -> Vec<Rank> {
+    let rank = |bit: u32| match bit {
+        0 => Rank::Ace,
+        _ => Rank::iter().nth(bit as usize - 1).unwrap_or(Rank::Ace),
+    };
+    (0..13)
+        .rev()
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(rank)
+        .collect()
+}
@@ -1,133 +1,404 @@
-mod code;
-mod data;
-mod test;
+use clap::{Args, Parser, Subcommand};
+use poker::{
+    Call, Deck, EquityConfig, Format, Game, PokerError, Range, Rules, SimulationConfig, StudHand,
+    Table,
+};
+use std::error::Error;
+#[cfg(feature = "lookup")]
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::data::{Card, Deck, Hand, Rank, Suit};
-use itertools::Itertools;
-use std::time::Instant;
-use strum::IntoEnumIterator;
-
-fn _propability_to_win() {
-    let players = 4;
-    let iterations = 1_000_000;
-    let timer = Instant::now();
-
-    let mut wins_per_hand = vec![0; Hand::iter().count()];
-    let mut losses_per_hand = vec![0; Hand::iter().count()];
+#[derive(Parser)]
+#[command(about = "Poker hand evaluation, equity and simulation")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output as `text`, `json` or `csv`.
+    #[arg(long, global = true, default_value = "text", value_parser = parse_format)]
+    format: Format,
+}
 
-    for _ in 0..iterations {
-        let mut deck = Deck::french().shuffle();
+#[derive(Subcommand)]
+enum Command {
+    /// Equity of hands or ranges against each other, like `AhKh QsQd`.
+    Equity {
+        /// Hold'em hands or ranges, Omaha hands with `--omaha` or stud
+        /// hands like `AsKd/Qh` with `--stud`.
+        #[arg(required = true, num_args = 2..)]
+        hands: Vec<String>,
+        #[arg(long, default_value = "")]
+        board: Deck,
+        /// Cards known to be out of the deck.
+        #[arg(long, default_value = "")]
+        dead: Deck,
+        /// Runouts dealt when the spot is too large to enumerate.
+        #[arg(long, default_value = "100000", value_parser = parse_count)]
+        samples: usize,
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Stop sampling once no equity has a standard error above this.
+        #[arg(long)]
+        precision: Option<f64>,
+        /// Stop sampling after this many seconds.
+        #[arg(long, value_parser = parse_seconds)]
+        time_budget: Option<Duration>,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Chance of winning with every hand category in random deals.
+    Simulate {
+        #[arg(long, default_value = "4")]
+        players: usize,
+        #[arg(long, default_value = "1e6", value_parser = parse_count)]
+        iterations: usize,
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Worker threads, zero uses every available core.
+        #[arg(long, default_value = "0")]
+        threads: usize,
+        /// Stop once no standard error is above this.
+        #[arg(long)]
+        precision: Option<f64>,
+        /// Stop after this many seconds.
+        #[arg(long, value_parser = parse_seconds)]
+        time_budget: Option<Duration>,
+        /// Lookup table cache, built and saved here when missing. Without
+        /// it the table is built in memory for this run.
+        #[cfg(feature = "lookup")]
+        #[arg(long)]
+        lookup: Option<PathBuf>,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Cards that improve a hand on the flop or turn, and the chance to
+    /// improve by the river.
+    Outs {
+        #[arg(long)]
+        hand: Deck,
+        #[arg(long)]
+        board: Deck,
+        /// Opponent hands or ranges, outs that help them are not clean.
+        #[arg(long, value_parser = parse_range)]
+        against: Vec<Range>,
+        #[command(flatten)]
+        rules: RulesArgs,
+    },
+    /// Whether calling a bet pays, with a known equity or the equity of a
+    /// hand against the opponent ranges, a random hand if none are given.
+    Odds {
+        #[arg(long)]
+        pot: f64,
+        /// Bet to call, already counted in the pot.
+        #[arg(long)]
+        call: f64,
+        /// Effective stack before calling, for implied odds.
+        #[arg(long)]
+        stack: Option<f64>,
+        #[arg(long, required_unless_present = "hand")]
+        equity: Option<f64>,
+        #[arg(long, requires = "board", conflicts_with = "equity")]
+        hand: Option<Deck>,
+        #[arg(long)]
+        board: Option<Deck>,
+        #[arg(long, value_parser = parse_range)]
+        against: Vec<Range>,
+    },
+}
 
-        let table = deck.deal(5);
+#[derive(Args)]
+struct RulesArgs {
+    /// Six plus hold'em on the 36 card deck, a flush beats a full house.
+    #[arg(long)]
+    short_deck: bool,
+    /// In a short deck, three of a kind beats a straight.
+    #[arg(long, requires = "short_deck")]
+    trips_beat_straight: bool,
+}
 
-        let hands = (0..players).map(|_| deck.deal(2)).collect::<Vec<_>>();
+impl RulesArgs {
+    fn rules(&self) -> Rules {
+        match self.short_deck {
+            true => Rules::ShortDeck {
+                trips_beat_straight: self.trips_beat_straight,
+            },
+            false => Rules::Standard,
+        }
+    }
+}
 
-        let mut hands: Vec<_> = hands
-            .into_iter()
-            .map(|hand| Deck::best_hand(&table, hand))
-            .sorted()
-            .collect();
+#[derive(Args)]
+struct GameArgs {
+    #[command(flatten)]
+    rules: RulesArgs,
+    /// Omaha with this many hole cards, four unless given like `--omaha=5`.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "4",
+        group = "split_game",
+        value_parser = clap::value_parser!(u64).range(4..=6)
+    )]
+    omaha: Option<u64>,
+    /// Split every Omaha or stud pot with the best eight-or-better low.
+    #[arg(long, requires = "split_game")]
+    hi_lo: bool,
+    /// Badugi, four cards each and no board.
+    #[arg(long, conflicts_with_all = ["omaha", "short_deck"])]
+    badugi: bool,
+    /// Seven-card stud, hands written as down cards, a slash and up cards.
+    #[arg(long, group = "split_game", conflicts_with_all = ["omaha", "badugi"])]
+    stud: bool,
+}
 
-        if let Some(winner) = hands.pop() {
-            let idx: usize = winner.into();
-            wins_per_hand[idx] += 1;
+impl GameArgs {
+    fn game(&self) -> Game {
+        if self.badugi {
+            return Game::Badugi;
         }
-
-        for hand in hands {
-            let idx: usize = hand.into();
-            losses_per_hand[idx] += 1;
+        if self.stud {
+            return Game::Stud { hi_lo: self.hi_lo };
         }
-    }
-
-    let hand_title = "Hand";
-    let hand_probability_title = "Hand probability";
-    let probability_to_win_title = "Probability to win";
-
-    println!("Players: {}", players);
-
-    println!(
-        "{: <14}: {: >hp$}, {: >ptw$}",
-        hand_title,
-        hand_probability_title,
-        probability_to_win_title,
-        hp = hand_probability_title.len(),
-        ptw = probability_to_win_title.len()
-    );
-
-    for (idx, (wins, loses)) in
-        Iterator::zip(wins_per_hand.iter(), losses_per_hand.iter()).enumerate()
-    {
-        let hand_probability = *wins as f64 / iterations as f64 * 100.0;
-        let probability_to_win = *wins as f64 / (*wins + *loses) as f64 * 100.0;
-
-        let hand = Hand::from(idx);
-        if let Some(hand) = format!("{}", hand).split("(").next() {
-            println!(
-                "{: <14}: {: >hp$.2}%, {: >ptw$.2}%",
-                hand,
-                hand_probability,
-                probability_to_win,
-                hp = hand_probability_title.len() - 1,
-                ptw = probability_to_win_title.len() - 1
-            );
+        match self.omaha {
+            Some(hole_cards) => Game::Omaha {
+                hole_cards: hole_cards as usize,
+                hi_lo: self.hi_lo,
+            },
+            None => Game::Holdem,
         }
     }
-
-    println!(
-        "Simulated {:e} games in {:.2?}",
-        iterations,
-        timer.elapsed()
-    );
 }
 
-fn _ways_to_improve_a_hand() {
-    let mut deck = Deck::french().shuffle();
+/// All 1326 starting hands.
+const ANY_HAND: &str = "22+, A2+, K2+, Q2+, J2+, T2+, 92+, 82+, 72+, 62+, 52+, 42+, 32";
 
-    let mut table = deck.deal(4);
+fn parse_range(s: &str) -> Result<Range, String> {
+    Range::parse(s)
+        .ok_or_else(|| format!("{:?} is not a hand or range like \"AhKh\" or \"QQ+\"", s))
+}
 
-    let hand = deck.deal(2);
-    let best_hand = Deck::best_hand(&table, hand.clone());
+/// Parses counts written plainly or in scientific notation, like `1e7`.
+fn parse_count(s: &str) -> Result<usize, String> {
+    if let Ok(count) = s.parse() {
+        return Ok(count);
+    }
+    match s.parse::<f64>() {
+        Ok(count) if count >= 0.0 && count.fract() == 0.0 && count <= usize::MAX as f64 => {
+            Ok(count as usize)
+        }
+        _ => Err(format!("{:?} is not a whole number", s)),
+    }
+}
 
-    let cards_that_improve_hand: Vec<_> = deck
-        .cards
-        .iter()
-        .filter_map(|card| {
-            table.cards.push(*card);
-            let possible_hands = Deck::all_hands(&table, hand.clone());
-            table.cards.pop();
+/// Parses a non-negative number of seconds, like `2.5`.
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("{:?} is not a number of seconds", s))
+}
 
-            let ace = Card::new(Rank::Ace, Suit::Clubs);
-            let possible_hands: Vec<_> = possible_hands
-                .into_iter()
-                .filter(|hand| hand > &best_hand)
-                .filter(|hand| hand > &Hand::TwoPair([ace, ace], [ace, ace]))
-                .collect();
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s.to_lowercase().as_str() {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(format!("{:?} is not one of text, json, csv", s)),
+    }
+}
 
-            if possible_hands.is_empty() {
-                return None;
+fn equity(
+    hands: &[String],
+    board: &Deck,
+    dead: &Deck,
+    config: &EquityConfig,
+) -> Result<Table, Box<dyn Error>> {
+    if config.game == Game::Holdem {
+        let ranges = hands
+            .iter()
+            .map(|hand| parse_range(hand))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Range::try_equity(&ranges, board, dead, config)?.table());
+    }
+
+    let (mut table, hands) = match config.game {
+        Game::Stud { .. } => {
+            if !board.cards.is_empty() {
+                return Err(PokerError::NoBoard.into());
             }
+            let hands = hands
+                .iter()
+                .map(|hand| StudHand::parse(hand))
+                .collect::<Result<Vec<_>, _>>()?;
+            let table = Deck::try_stud_equity(&hands, dead, config)?.table();
+            (
+                table,
+                hands.iter().map(|hand| format!("{:#}", hand)).collect(),
+            )
+        }
+        _ => {
+            let hands = hands
+                .iter()
+                .map(|hand| Deck::parse(hand))
+                .collect::<Result<Vec<_>, _>>()?;
+            let table = Deck::try_equity(&hands, board, dead, config)?.table();
+            (
+                table,
+                hands
+                    .iter()
+                    .map(|hand| format!("{:#}", hand))
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+    table.columns.insert(1, "Hand".to_string());
+    for (row, hand) in table.rows.iter_mut().zip(hands) {
+        row.insert(1, hand.into());
+    }
+    Ok(table)
+}
 
-            Some((card, possible_hands))
-        })
-        .sorted_by_key(|(_, hands)| *hands.first().unwrap())
-        .collect();
+#[cfg(feature = "lookup")]
+fn simulate(config: &SimulationConfig, path: Option<PathBuf>) -> Result<Table, PokerError> {
+    use poker::LookupTable;
+
+    // The lookup table only knows the standard rankings
+    let report = match config.rules {
+        Rules::Standard => {
+            let lookup = match path {
+                Some(path) => LookupTable::load_or_build(&path).map_err(|err| {
+                    PokerError::LookupFile(format!("{}: {}", path.display(), err))
+                })?,
+                None => LookupTable::build(),
+            };
+            config.try_run_with(|set| lookup.evaluate(set))?
+        }
+        _ => config.try_run()?,
+    };
+    Ok(report.table())
+}
 
-    println!("Table({})", table.rows(&5));
-    println!("Hand({}), {}", hand.rows(&2), best_hand);
+#[cfg(not(feature = "lookup"))]
+fn simulate(config: &SimulationConfig) -> Result<Table, PokerError> {
+    Ok(config.try_run()?.table())
+}
 
-    for (card, hands) in &cards_that_improve_hand {
-        let card = format!("{}", card);
-        print!("{: >4}: ", card);
-        for hand in hands {
-            print!("{}, ", hand);
+fn odds(
+    call: &Call,
+    equity: Option<f64>,
+    hand: Option<Deck>,
+    board: Option<Deck>,
+    against: &[Range],
+) -> Result<Table, PokerError> {
+    let equity = match (equity, hand, board) {
+        (Some(equity), _, _) => equity,
+        (None, Some(hand), Some(board)) => {
+            let [first, second] = hand.cards[..] else {
+                return Err(PokerError::HandSize(hand.cards.len()));
+            };
+            // Without opponents the hand plays a single random one
+            let any = [Range::parse(ANY_HAND).expect("Every hand is a valid range")];
+            let against = match against.is_empty() {
+                true => &any[..],
+                false => against,
+            };
+            let ranges: Vec<_> = std::iter::once(Range::from([first, second]))
+                .chain(against.iter().cloned())
+                .collect();
+            let config = EquityConfig::default();
+            Range::try_equity(&ranges, &board, &Deck::default(), &config)?.players[0]
+                .equity_estimate()
+                .value
         }
-        println!();
-    }
+        _ => unreachable!("Arguments require an equity or a hand and board"),
+    };
+    Ok(call.try_odds(equity)?.table(call))
+}
 
-    println!("Better hands: {}", cards_that_improve_hand.len());
+fn run(command: Command) -> Result<Table, Box<dyn Error>> {
+    let table = match command {
+        Command::Equity {
+            hands,
+            board,
+            dead,
+            samples,
+            seed,
+            precision,
+            time_budget,
+            game,
+        } => {
+            let config = EquityConfig {
+                samples,
+                seed,
+                precision,
+                time_budget,
+                rules: game.rules.rules(),
+                game: game.game(),
+                ..EquityConfig::default()
+            };
+            equity(&hands, &board, &dead, &config)?
+        }
+        Command::Simulate {
+            players,
+            iterations,
+            seed,
+            threads,
+            precision,
+            time_budget,
+            #[cfg(feature = "lookup")]
+            lookup,
+            game,
+        } => {
+            let config = SimulationConfig {
+                players,
+                iterations,
+                seed,
+                threads,
+                precision,
+                time_budget,
+                rules: game.rules.rules(),
+                game: game.game(),
+                ..SimulationConfig::default()
+            };
+            #[cfg(feature = "lookup")]
+            let table = simulate(&config, lookup)?;
+            #[cfg(not(feature = "lookup"))]
+            let table = simulate(&config)?;
+            table
+        }
+        Command::Outs {
+            hand,
+            board,
+            against,
+            rules,
+        } => Deck::try_outs_with(&hand, &board, &against, rules.rules())?.table(),
+        Command::Odds {
+            pot,
+            call,
+            stack,
+            equity,
+            hand,
+            board,
+            against,
+        } => {
+            let call = Call {
+                pot,
+                to_call: call,
+                stack,
+            };
+            odds(&call, equity, hand, board, &against)?
+        }
+    };
+    Ok(table)
 }
 
 fn main() {
-    // _propability_to_win();
-    _ways_to_improve_a_hand();
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(table) => print!("{}", table.render(cli.format)),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,54 @@
+use crate::data::{Call, CallOdds, Outs, PokerError};
+
+impl Call {
+    /// Pot odds of the call with `equity`, the chance of winning the pot.
+    /// Without a stack only the direct odds decide if it is profitable.
+    pub fn odds(&self, equity: f64) -> CallOdds {
+        self.try_odds(equity)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `odds`, but fails on an equity outside 0 to 1 or a pot or bet
+    /// that is not positive.
+    pub fn try_odds(&self, equity: f64) -> Result<CallOdds, PokerError> {
+        if !(0.0..=1.0).contains(&equity) {
+            return Err(PokerError::InvalidOdds(format!(
+                "equity {} is not between 0 and 1",
+                equity
+            )));
+        }
+        for (name, amount) in [("pot", self.pot), ("bet to call", self.to_call)] {
+            if amount.is_nan() || amount <= 0.0 {
+                return Err(PokerError::InvalidOdds(format!(
+                    "{} {} is not positive",
+                    name, amount
+                )));
+            }
+        }
+
+        let break_even = self.to_call / (self.pot + self.to_call);
+        let ev = equity * self.pot - (1.0 - equity) * self.to_call;
+        let implied = match ev >= 0.0 {
+            true => 0.0,
+            false if equity > 0.0 => -ev / equity,
+            false => f64::INFINITY,
+        };
+        let behind = self.stack.map_or(0.0, |stack| stack - self.to_call);
+
+        Ok(CallOdds {
+            equity,
+            break_even,
+            ev,
+            implied,
+            profitable: ev >= 0.0 || implied <= behind,
+        })
+    }
+}
+
+impl Outs {
+    /// Chance that the next card is a clean out.
+    pub fn next_card(&self) -> f64 {
+        let clean = self.outs.iter().filter(|out| out.clean).count();
+        clean as f64 / self.unseen as f64
+    }
+}
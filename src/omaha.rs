@@ -0,0 +1,132 @@
+use crate::data::{CardSet, Deck, Game, Hand, PokerError, Rules, Strength};
+use strum::IntoEnumIterator;
+
+/// Badugi categories by the number of cards that play.
+const BADUGI_CATEGORIES: [&str; 4] = ["OneCard", "TwoCards", "ThreeCards", "Badugi"];
+
+impl Game {
+    /// Hole cards dealt to every player.
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            Game::Holdem => 2,
+            Game::Omaha { hole_cards, .. } => *hole_cards,
+            Game::Badugi => 4,
+            Game::Stud { .. } => 7,
+        }
+    }
+
+    /// Fails unless `hand` holds the hole cards of this game.
+    pub fn check_hand(&self, hand: &Deck) -> Result<(), PokerError> {
+        let n = hand.cards.len();
+        match self {
+            Game::Holdem if n != 2 => Err(PokerError::HandSize(n)),
+            Game::Omaha { .. } if !(4..=6).contains(&n) => Err(PokerError::OmahaHandSize(n)),
+            Game::Badugi if n != 4 => Err(PokerError::BadugiHandSize(n)),
+            Game::Stud { .. } if n > 7 => Err(PokerError::StudHandSize(n)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether community cards are dealt, which equity runs out.
+    pub fn has_board(&self) -> bool {
+        matches!(self, Game::Holdem | Game::Omaha { .. })
+    }
+
+    /// Community cards dealt by the river.
+    pub fn board_cards(&self) -> usize {
+        match self.has_board() {
+            true => 5,
+            false => 0,
+        }
+    }
+
+    /// Number of hand categories a simulation tallies.
+    pub fn categories(&self) -> usize {
+        match self {
+            Game::Badugi => BADUGI_CATEGORIES.len(),
+            _ => Hand::iter().count(),
+        }
+    }
+
+    /// Name of hand category `category`, like "FullHouse" or "Badugi".
+    pub fn category_name(&self, category: usize) -> &'static str {
+        match self {
+            Game::Badugi => BADUGI_CATEGORIES[category],
+            _ => Hand::from(category).name(),
+        }
+    }
+
+    /// Strength of the best hand of `hole` on `board` under `rules`.
+    pub fn evaluate(&self, hole: CardSet, board: CardSet, rules: Rules) -> Strength {
+        self.evaluate_with(hole, board, |set| set.evaluate_with(rules))
+    }
+
+    /// Best hand with a custom evaluator of five to seven cards, which in
+    /// Omaha only ever sees two hole and three board cards. Badugi hands
+    /// are ranked by `CardSet::badugi` instead.
+    pub fn evaluate_with<F>(&self, hole: CardSet, board: CardSet, evaluate: F) -> Strength
+    where
+        F: Fn(CardSet) -> Strength,
+    {
+        match self {
+            Game::Holdem | Game::Badugi | Game::Stud { .. } => evaluate(hole | board),
+            Game::Omaha { .. } => omaha_sets(hole, board)
+                .map(evaluate)
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Deck {
+    /// Best Omaha hand of four to six hole cards, panics on invalid input.
+    pub fn omaha_best_hand(table: &Deck, hand: Deck) -> Hand {
+        Self::try_omaha_best_hand(table, hand).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Best hand of exactly two cards of `hand` and three of `table`.
+    pub fn try_omaha_best_hand(table: &Deck, hand: Deck) -> Result<Hand, PokerError> {
+        Game::Omaha {
+            hole_cards: 4,
+            hi_lo: false,
+        }
+        .check_hand(&hand)?;
+        if !(3..=5).contains(&table.cards.len()) {
+            return Err(PokerError::TableSize(table.cards.len()));
+        }
+        Self::check_unique([table, &hand])?;
+
+        let best = omaha_sets(CardSet::from(&hand), CardSet::from(table))
+            .max_by_key(|set| set.evaluate())
+            .expect("Omaha hands have two hole and three board cards");
+        Ok(best.best_hand())
+    }
+}
+
+/// Every set of two hole cards and three board cards, without allocating.
+pub(crate) fn omaha_sets(hole: CardSet, board: CardSet) -> impl Iterator<Item = CardSet> {
+    let (hole, holes) = bits::<6>(hole);
+    let (board, boards) = bits::<5>(board);
+
+    let pairs = (0..holes).flat_map(move |a| (a + 1..holes).map(move |b| hole[a] | hole[b]));
+    pairs.flat_map(move |pair| {
+        (0..boards).flat_map(move |a| {
+            (a + 1..boards).flat_map(move |b| {
+                (b + 1..boards).map(move |c| CardSet(pair | board[a] | board[b] | board[c]))
+            })
+        })
+    })
+}
+
+/// Single card masks of the first `N` cards of the set and how many there are.
+fn bits<const N: usize>(set: CardSet) -> ([u64; N], usize) {
+    let mut bits = [0; N];
+    let mut rest = set.0;
+    let mut n = 0;
+    while rest != 0 && n < N {
+        bits[n] = rest & rest.wrapping_neg();
+        rest &= rest - 1;
+        n += 1;
+    }
+    (bits, n)
+}
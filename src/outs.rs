@@ -0,0 +1,165 @@
+use crate::data::{CardSet, Deck, Out, Outs, PokerError, Range, Rules, Strength};
+use itertools::Itertools;
+
+impl Deck {
+    /// Outs of `hand` on a flop or turn `board` against the `opponents`.
+    /// Opponents holding a single combo are known hands, their cards can't
+    /// come. Panics on invalid input.
+    pub fn outs(hand: &Deck, board: &Deck, opponents: &[Range]) -> Outs {
+        Self::try_outs(hand, board, opponents).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `outs`, but fails instead of panicking on invalid input.
+    pub fn try_outs(hand: &Deck, board: &Deck, opponents: &[Range]) -> Result<Outs, PokerError> {
+        Self::try_outs_with(hand, board, opponents, Rules::Standard)
+    }
+
+    /// Outs under `rules`, drawn from the deck the rules are played with.
+    /// Panics on invalid input.
+    pub fn outs_with(hand: &Deck, board: &Deck, opponents: &[Range], rules: Rules) -> Outs {
+        Self::try_outs_with(hand, board, opponents, rules).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `outs_with`, but fails instead of panicking on invalid input.
+    pub fn try_outs_with(
+        hand: &Deck,
+        board: &Deck,
+        opponents: &[Range],
+        rules: Rules,
+    ) -> Result<Outs, PokerError> {
+        Self::check_holding(board, hand)?;
+        if board.cards.len() > 4 {
+            return Err(PokerError::OutsBoard(board.cards.len()));
+        }
+
+        let hole = CardSet::from(hand);
+        let board_set = CardSet::from(board);
+        let current = (board_set | hole).evaluate_with(rules);
+
+        // Known hands of the opponents, out of every other range
+        let known: Vec<CardSet> = opponents
+            .iter()
+            .map(|range| match range.len() {
+                1 => range.combos[0].cards.into_iter().collect(),
+                _ => CardSet::default(),
+            })
+            .collect();
+        let dead = known
+            .iter()
+            .fold(CardSet::default(), |dead, &cards| dead | cards);
+
+        let mut combos = vec![];
+        for (player, range) in opponents.iter().enumerate() {
+            let others = known
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != player)
+                .fold(CardSet::default(), |others, (_, &cards)| others | cards);
+            let mut kept = range
+                .combos
+                .iter()
+                .map(|combo| combo.cards.into_iter().collect::<CardSet>())
+                .filter(|cards| cards.is_disjoint(hole | board_set | others))
+                .peekable();
+            if kept.peek().is_none() {
+                return Err(PokerError::EmptyRange(player));
+            }
+            combos.extend(kept.map(|cards| (cards, (board_set | cards).evaluate_with(rules))));
+        }
+
+        let mut unseen = rules.deck();
+        unseen
+            .cards
+            .retain(|card| !(hole | board_set | dead).contains(*card));
+
+        // A higher category that the hole cards make, not one the board
+        // makes for everyone
+        let improved = |table: CardSet| {
+            let strength = (table | hole).evaluate_with(rules);
+            let ours = strength.category() > current.category()
+                && made(strength) > made(table.evaluate_with(rules));
+            ours.then_some(strength)
+        };
+        // An opponent combo not blocked by the runout that improves to a
+        // hand at least as strong as ours
+        let dirty = |table: CardSet, ours: Strength| {
+            combos.iter().any(|&(cards, before)| {
+                if !cards.is_disjoint(table) {
+                    return false;
+                }
+                let after = (table | cards).evaluate_with(rules);
+                after.category() > before.category() && after >= ours
+            })
+        };
+
+        let mut outs: Vec<Out> = unseen
+            .cards
+            .iter()
+            .filter_map(|&card| {
+                let mut table = board_set;
+                table.insert(card);
+                let strength = improved(table)?;
+                Some(Out {
+                    card,
+                    hand: (table | hole).best_hand_with(rules),
+                    clean: !dirty(table, strength),
+                })
+            })
+            .collect();
+        outs.sort_by(|a, b| {
+            let strength = |out: &Out| out.hand.strength_with(rules);
+            strength(b).cmp(&strength(a)).then(a.card.cmp(&b.card))
+        });
+
+        let (by_river, clean_by_river, runner_runner) = match board.cards.len() {
+            4 => {
+                let unseen = unseen.cards.len() as f64;
+                let clean = outs.iter().filter(|out| out.clean).count();
+                (outs.len() as f64 / unseen, clean as f64 / unseen, 0.0)
+            }
+            _ => {
+                let out_cards: CardSet = outs.iter().map(|out| out.card).collect();
+                let (mut hits, mut clean, mut runner_runner, mut runouts) = (0, 0, 0, 0);
+                for (turn, river) in unseen.cards.iter().copied().tuple_combinations() {
+                    runouts += 1;
+                    let mut table = board_set;
+                    table.insert(turn);
+                    table.insert(river);
+                    let Some(strength) = improved(table) else {
+                        continue;
+                    };
+                    hits += 1;
+                    if !dirty(table, strength) {
+                        clean += 1;
+                    }
+                    if !out_cards.contains(turn) && !out_cards.contains(river) {
+                        runner_runner += 1;
+                    }
+                }
+                let rate = |count| count as f64 / runouts as f64;
+                (rate(hits), rate(clean), rate(runner_runner))
+            }
+        };
+
+        Ok(Outs {
+            hand: (board_set | hole).best_hand_with(rules),
+            outs,
+            unseen: unseen.cards.len(),
+            by_river,
+            clean_by_river,
+            runner_runner,
+        })
+    }
+}
+
+/// Strength without its kickers, the cards that make the category.
+fn made(strength: Strength) -> u32 {
+    let kickers = match strength.category() {
+        0 => 4,
+        1 => 3,
+        2 | 7 => 1,
+        3 => 2,
+        _ => 0,
+    };
+    strength.0 & !((1 << (4 * kickers)) - 1)
+}
@@ -0,0 +1,292 @@
+use crate::data::{Card, CardSet, Combo, Deck, Range, Rank, Suit};
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::fmt::Display;
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Pair,
+    Suited,
+    Offsuit,
+    Any,
+}
+
+impl Range {
+    /// Parses comma separated range notation: pairs ("TT", "22+", "22-55"),
+    /// suited or offsuit hands ("AKs", "KTo+", "A2s-A5s", "QJ"), exact
+    /// combos ("AhKh"), each optionally weighted as in "AKs:0.5".
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut range = Range::default();
+
+        for token in s
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+        {
+            let (hands, weight) = match token.split_once(':') {
+                Some((hands, weight)) => (hands.trim(), weight.trim().parse().ok()?),
+                None => (token, 1.0),
+            };
+            if !(0.0..=1.0).contains(&weight) {
+                return None;
+            }
+
+            for cards in Self::expand(hands)? {
+                range.insert(cards, weight);
+            }
+        }
+
+        Some(range)
+    }
+
+    fn expand(token: &str) -> Option<Vec<[Card; 2]>> {
+        let chars: Vec<char> = token.chars().collect();
+
+        if let [r1, s1, r2, s2] = chars[..] {
+            if let (Some(s1), Some(s2)) = (Suit::from_char(s1), Suit::from_char(s2)) {
+                let first = Card::new(Rank::from_char(r1)?, s1);
+                let second = Card::new(Rank::from_char(r2)?, s2);
+                if first == second {
+                    return None;
+                }
+                return Some(vec![ordered(first, second)]);
+            }
+        }
+
+        let (high, lows, kind) = if let Some((from, to)) = token.split_once('-') {
+            let (high, from, kind) = class(from)?;
+            let (to_high, to, to_kind) = class(to)?;
+            if kind != to_kind || (kind != Kind::Pair && high != to_high) {
+                return None;
+            }
+            let (bottom, top) = (from.min(to), from.max(to));
+            let lows = Rank::iter()
+                .filter(|&rank| bottom <= rank && rank <= top)
+                .collect();
+            (high, lows, kind)
+        } else if let Some(token) = token.strip_suffix('+') {
+            let (high, low, kind) = class(token)?;
+            let lows = Rank::iter()
+                .filter(|&rank| low <= rank && (kind == Kind::Pair || rank < high))
+                .collect();
+            (high, lows, kind)
+        } else {
+            let (high, low, kind) = class(token)?;
+            (high, vec![low], kind)
+        };
+
+        let combos = lows
+            .into_iter()
+            .flat_map(|low| {
+                let high = if kind == Kind::Pair { low } else { high };
+                combos(high, low, kind)
+            })
+            .collect();
+
+        Some(combos)
+    }
+
+    /// Adds a combo, replacing the weight if it is already in the range.
+    pub fn insert(&mut self, cards: [Card; 2], weight: f64) {
+        let cards = ordered(cards[0], cards[1]);
+        match self.combos.iter_mut().find(|combo| combo.cards == cards) {
+            Some(combo) => combo.weight = weight,
+            None => self.combos.push(Combo { cards, weight }),
+        }
+    }
+
+    /// Drops every combo holding one of the `dead` cards.
+    pub fn remove_blocked(&mut self, dead: &Deck) {
+        let dead = CardSet::from(dead);
+        self.combos
+            .retain(|combo| !combo.cards.iter().any(|card| dead.contains(*card)));
+    }
+
+    /// Number of combos, whatever their weight.
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Whether the range has no combo.
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Sum of combo weights, the effective number of combos in the range.
+    pub fn total_weight(&self) -> f64 {
+        self.combos.iter().map(|combo| combo.weight).sum()
+    }
+
+    /// Weight of every combo in the class, if the whole class is in range
+    /// with a single weight.
+    fn class_weight(&self, high: Rank, low: Rank, kind: Kind) -> Option<f64> {
+        let weights: Vec<_> = combos(high, low, kind)
+            .into_iter()
+            .map(|cards| self.combos.iter().find(|combo| combo.cards == cards))
+            .map(|combo| combo.map(|combo| combo.weight))
+            .collect::<Option<_>>()?;
+
+        weights
+            .iter()
+            .all(|weight| *weight == weights[0])
+            .then_some(weights[0])
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut tokens = vec![];
+        let mut grouped = vec![];
+
+        let ranks: Vec<Rank> = Rank::iter().rev().collect();
+
+        // Pairs, from aces down
+        let weights: Vec<_> = ranks
+            .iter()
+            .map(|&rank| self.class_weight(rank, rank, Kind::Pair))
+            .collect();
+        for (top, bottom, weight) in runs(&weights) {
+            let (top, bottom) = (ranks[top], ranks[bottom]);
+            let token = match (top, bottom) {
+                _ if top == bottom => format!("{0}{0}", top.to_char()),
+                (Rank::Ace, _) => format!("{0}{0}+", bottom.to_char()),
+                _ => format!("{0}{0}-{1}{1}", top.to_char(), bottom.to_char()),
+            };
+            tokens.push(weighted(token, weight));
+            grouped.extend(
+                ranks
+                    .iter()
+                    .filter(|&&rank| bottom <= rank && rank <= top)
+                    .flat_map(|&rank| combos(rank, rank, Kind::Pair)),
+            );
+        }
+
+        // Suited and offsuit hands, kickers from one below the high card down
+        for (i, &high) in ranks.iter().enumerate() {
+            let lows = &ranks[i + 1..];
+            let runs_of = |kind| {
+                let weights: Vec<_> = lows
+                    .iter()
+                    .map(|&low| self.class_weight(high, low, kind))
+                    .collect();
+                runs(&weights)
+            };
+
+            // Runs present both suited and offsuit print once without suffix
+            let suited = runs_of(Kind::Suited);
+            let offsuit = runs_of(Kind::Offsuit);
+            let kinds = suited
+                .iter()
+                .map(|run| match offsuit.contains(run) {
+                    true => (*run, Kind::Any, ""),
+                    false => (*run, Kind::Suited, "s"),
+                })
+                .chain(
+                    offsuit
+                        .iter()
+                        .filter(|run| !suited.contains(run))
+                        .map(|run| (*run, Kind::Offsuit, "o")),
+                )
+                .sorted_by_key(|((top_index, _, _), _, _)| *top_index);
+
+            for ((top_index, bottom_index, weight), kind, suffix) in kinds {
+                let (top, bottom) = (lows[top_index], lows[bottom_index]);
+                let (h, t, b) = (high.to_char(), top.to_char(), bottom.to_char());
+                let token = match top_index {
+                    _ if top == bottom => format!("{}{}{}", h, t, suffix),
+                    0 => format!("{}{}{}+", h, b, suffix),
+                    _ => format!("{0}{1}{3}-{0}{2}{3}", h, t, b, suffix),
+                };
+                tokens.push(weighted(token, weight));
+                grouped.extend(
+                    lows.iter()
+                        .filter(|&&low| bottom <= low && low <= top)
+                        .flat_map(|&low| combos(high, low, kind)),
+                );
+            }
+        }
+
+        // Whatever is left of partially blocked classes
+        for combo in self
+            .combos
+            .iter()
+            .filter(|combo| !grouped.contains(&combo.cards))
+            .sorted_by_key(|combo| Reverse(combo.cards.map(|card| card.rank)))
+        {
+            let [first, second] = combo.cards;
+            let token = format!("{:#}{:#}", first, second);
+            tokens.push(weighted(token, combo.weight));
+        }
+
+        write!(f, "{}", tokens.join(", "))
+    }
+}
+
+impl From<[Card; 2]> for Range {
+    fn from(cards: [Card; 2]) -> Self {
+        let mut range = Range::default();
+        range.insert(cards, 1.0);
+        range
+    }
+}
+
+/// Splits a class token such as "AKs" into high rank, low rank and kind.
+fn class(token: &str) -> Option<(Rank, Rank, Kind)> {
+    let chars: Vec<char> = token.chars().collect();
+    let (high, low, kind) = match chars[..] {
+        [r1, r2] => (r1, r2, None),
+        [r1, r2, kind] => (r1, r2, Some(kind.to_ascii_lowercase())),
+        _ => return None,
+    };
+
+    let (high, low) = (Rank::from_char(high)?, Rank::from_char(low)?);
+    let kind = match kind {
+        None if high == low => Kind::Pair,
+        None => Kind::Any,
+        Some('s') if high != low => Kind::Suited,
+        Some('o') if high != low => Kind::Offsuit,
+        _ => return None,
+    };
+
+    Some((high.max(low), high.min(low), kind))
+}
+
+fn combos(high: Rank, low: Rank, kind: Kind) -> Vec<[Card; 2]> {
+    itertools::iproduct!(Suit::iter(), Suit::iter())
+        .filter(|(s1, s2)| match kind {
+            Kind::Pair => s1 < s2,
+            Kind::Suited => s1 == s2,
+            Kind::Offsuit => s1 != s2,
+            Kind::Any => true,
+        })
+        .map(|(s1, s2)| ordered(Card::new(high, s1), Card::new(low, s2)))
+        .collect()
+}
+
+fn ordered(first: Card, second: Card) -> [Card; 2] {
+    [first.max(second), first.min(second)]
+}
+
+/// Runs of consecutive equal weights as (first index, last index, weight).
+fn runs(weights: &[Option<f64>]) -> Vec<(usize, usize, f64)> {
+    let mut runs: Vec<(usize, usize, f64)> = vec![];
+    for (i, weight) in weights.iter().enumerate() {
+        let Some(weight) = *weight else {
+            continue;
+        };
+        match runs.last_mut() {
+            Some((_, last, w)) if *last + 1 == i && *w == weight => *last = i,
+            _ => runs.push((i, i, weight)),
+        }
+    }
+    runs
+}
+
+fn weighted(token: String, weight: f64) -> String {
+    if weight == 1.0 {
+        token
+    } else {
+        format!("{}:{}", token, weight)
+    }
+}
@@ -0,0 +1,470 @@
+use crate::data::{
+    Call, CallOdds, Deck, Equity, Estimate, Format, Outs, Range, RangeEquity, SimulationReport,
+    Table, Tally, Value,
+};
+use itertools::Itertools;
+use std::fmt::{self, Display, Write};
+
+impl Table {
+    /// Empty table with the named columns.
+    pub fn new(columns: &[&str]) -> Self {
+        Table {
+            fields: vec![],
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    /// Adds a value that applies to the whole report, like the seed.
+    pub fn field<V: Into<Value>>(mut self, name: &str, value: V) -> Self {
+        self.fields.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// Appends a row, panics unless it fills every column.
+    pub fn push(&mut self, row: Vec<Value>) {
+        assert_eq!(row.len(), self.columns.len(), "Row must fill every column");
+        self.rows.push(row);
+    }
+
+    /// Writes the report out. JSON is an object of the fields with the rows
+    /// under "rows". CSV repeats the fields as the first columns of every
+    /// row, or of a single row when there are none, and splits
+    /// probabilities into a value and a standard error column.
+    pub fn render(&self, format: Format) -> String {
+        let mut out = String::new();
+        match format {
+            Format::Text => self.text(&mut out),
+            Format::Json => self.json(&mut out),
+            Format::Csv => self.csv(&mut out),
+        }
+        .expect("Writing to a string cannot fail");
+        out
+    }
+
+    fn text(&self, out: &mut String) -> fmt::Result {
+        for (name, value) in &self.fields {
+            writeln!(out, "{}: {}", name, value)?;
+        }
+
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|i| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([self.columns[i].chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let header = self.columns.iter().cloned();
+        for row in [header.collect()].into_iter().chain(cells) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, &width))| match i {
+                    0 => format!("{: <width$}", cell),
+                    _ => format!("{: >width$}", cell),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(out, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+
+    fn json(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "{{")?;
+        for (name, value) in &self.fields {
+            writeln!(out, "  {}: {},", json_string(&key(name)), value.json())?;
+        }
+
+        writeln!(out, "  \"rows\": [")?;
+        for (i, row) in self.rows.iter().enumerate() {
+            let cells = self
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| format!("{}: {}", json_string(&key(column)), value.json()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let comma = if i + 1 < self.rows.len() { "," } else { "" };
+            writeln!(out, "    {{{}}}{}", cells, comma)?;
+        }
+        writeln!(out, "  ]")?;
+        writeln!(out, "}}")
+    }
+
+    fn csv(&self, out: &mut String) -> fmt::Result {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(_, value)| value.csv())
+            .collect::<Vec<_>>();
+
+        // Probability columns take two cells, decided by the first row
+        let split: Vec<bool> = match self.rows.first() {
+            Some(row) => row
+                .iter()
+                .map(|value| matches!(value, Value::Probability(_)))
+                .collect(),
+            None => vec![false; self.columns.len()],
+        };
+
+        let header = self
+            .fields
+            .iter()
+            .map(|(name, _)| key(name))
+            .chain(
+                self.columns
+                    .iter()
+                    .zip(&split)
+                    .flat_map(|(column, &split)| match split {
+                        true => vec![key(column), format!("{}_standard_error", key(column))],
+                        false => vec![key(column)],
+                    }),
+            )
+            .map(|cell| csv_cell(&cell))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(out, "{}", header)?;
+
+        // Without rows the fields still get a line, with the columns empty
+        if self.rows.is_empty() && !fields.is_empty() {
+            let empty = vec![String::new(); split.len()];
+            writeln!(out, "{}", fields.iter().chain(&empty).join(","))?;
+        }
+        for row in &self.rows {
+            let cells = row.iter().map(|value| match value {
+                Value::Probability(estimate) => {
+                    format!("{},{}", estimate.value, estimate.standard_error)
+                }
+                value => value.csv(),
+            });
+            writeln!(out, "{}", fields.iter().cloned().chain(cells).join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(Format::Text))
+    }
+}
+
+impl Value {
+    fn json(&self) -> String {
+        match self {
+            Value::Text(text) => json_string(text),
+            Value::Integer(n) => n.to_string(),
+            Value::Number(x) => json_number(*x),
+            Value::Bool(b) => b.to_string(),
+            Value::Probability(estimate) => format!(
+                "{{\"value\": {}, \"standard_error\": {}}}",
+                json_number(estimate.value),
+                json_number(estimate.standard_error)
+            ),
+        }
+    }
+
+    fn csv(&self) -> String {
+        match self {
+            Value::Text(text) => csv_cell(text),
+            Value::Number(x) => x.to_string(),
+            Value::Probability(estimate) => estimate.value.to_string(),
+            value => value.to_string(),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Text(text) => write!(f, "{}", text),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Number(x) => write!(f, "{:.2}", x),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Probability(estimate) if estimate.standard_error == 0.0 => {
+                write!(f, "{:.2}%", estimate.value * 100.0)
+            }
+            Value::Probability(estimate) => write!(
+                f,
+                "{:.2}% ±{:.2}",
+                estimate.value * 100.0,
+                estimate.margin() * 100.0
+            ),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Integer(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Integer(n as u64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Number(x)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<Estimate> for Value {
+    fn from(estimate: Estimate) -> Self {
+        Value::Probability(estimate)
+    }
+}
+
+impl SimulationReport {
+    /// Probability of winning with every hand category, and how often it
+    /// wins, ties or loses once made.
+    pub fn table(&self) -> Table {
+        let mut table = Table::new(&["Hand", "Hand probability", "Win", "Tie", "Loss"])
+            .field("Players", self.players)
+            .field("Iterations", self.iterations)
+            .field("Seed", seed(self.seed))
+            .field("Seconds", self.elapsed.as_secs_f64());
+
+        for (idx, tally) in self.hands.iter().enumerate() {
+            table.push(vec![
+                self.game.category_name(idx).into(),
+                self.hand_probability(idx).into(),
+                tally.win_estimate().into(),
+                tally.tie_estimate().into(),
+                tally.loss_estimate().into(),
+            ]);
+        }
+        table
+    }
+}
+
+impl Equity {
+    /// Equity, win and tie rate of every player, numbered from one. Hi-lo
+    /// games add how often each player scoops, wins only one half, and wins
+    /// each half alone or shares it.
+    pub fn table(&self) -> Table {
+        let mut columns = vec!["Player", "Equity", "Win", "Tie"];
+        if !self.splits.is_empty() {
+            columns.extend([
+                "Scoop",
+                "High only",
+                "Low only",
+                "High alone",
+                "High shared",
+                "Low alone",
+                "Low shared",
+            ]);
+        }
+        let mut table = Table::new(&columns)
+            .field("Runouts", self.runouts)
+            .field("Exact", self.exact)
+            .field("Seed", seed(self.seed));
+
+        for (player, tally) in self.players.iter().enumerate() {
+            let mut row = vec![(player + 1).into()];
+            row.extend(equity_row(tally, self.exact));
+            if let Some(split) = self.splits.get(player) {
+                let counts = [
+                    split.scoops,
+                    split.high_only,
+                    split.low_only,
+                    split.high_alone,
+                    split.high_shared,
+                    split.low_alone,
+                    split.low_shared,
+                ];
+                row.extend(counts.map(|count| share(count, self.runouts, self.exact)));
+            }
+            table.push(row);
+        }
+        table
+    }
+}
+
+impl RangeEquity {
+    /// Equity, win and tie rate of every player with the hands they were
+    /// dealt, which is the whole range unless some combo never came up.
+    /// Players with more than one combo are followed by a row per combo.
+    pub fn table(&self) -> Table {
+        let runouts = self.players.first().map_or(0, Tally::games);
+        let mut table = Table::new(&["Player", "Hand", "Equity", "Win", "Tie"])
+            .field("Runouts", runouts)
+            .field("Exact", self.exact)
+            .field("Seed", seed(self.seed));
+
+        for (player, (tally, combos)) in self.players.iter().zip(&self.combos).enumerate() {
+            let range = Range {
+                combos: combos.iter().map(|(combo, _)| *combo).collect(),
+            };
+            let mut row = vec![(player + 1).into(), range.to_string().into()];
+            row.extend(equity_row(tally, self.exact));
+            table.push(row);
+            if combos.len() < 2 {
+                continue;
+            }
+            for (combo, tally) in combos {
+                let cards = Deck::from(combo.cards.to_vec());
+                let mut row = vec![(player + 1).into(), format!("{:#}", cards).into()];
+                row.extend(equity_row(tally, self.exact));
+                table.push(row);
+            }
+        }
+        table
+    }
+}
+
+impl Outs {
+    /// Every out with the hand it makes, and the chances by the river.
+    pub fn table(&self) -> Table {
+        let exact = |value| Estimate {
+            value,
+            standard_error: 0.0,
+        };
+        let clean = self.outs.iter().filter(|out| out.clean).count();
+        let mut table = Table::new(&["Card", "Makes", "Hand", "Clean"])
+            .field("Best hand", format!("{:#}", self.hand))
+            .field("Unseen", self.unseen)
+            .field("Outs", self.outs.len())
+            .field("Clean outs", clean)
+            .field("By river", exact(self.by_river))
+            .field("Clean by river", exact(self.clean_by_river))
+            .field("Runner-runner", exact(self.runner_runner));
+
+        for out in &self.outs {
+            table.push(vec![
+                format!("{:#}", out.card).into(),
+                out.hand.name().into(),
+                format!("{:#}", out.hand).into(),
+                out.clean.into(),
+            ]);
+        }
+        table
+    }
+}
+
+impl CallOdds {
+    /// The call as fields and its odds as a single row.
+    pub fn table(&self, call: &Call) -> Table {
+        let mut table = Table::new(&["Equity", "Break-even", "EV", "Implied", "Profitable"])
+            .field("Pot", call.pot)
+            .field("To call", call.to_call);
+        if let Some(stack) = call.stack {
+            table = table.field("Stack", stack);
+        }
+
+        let exact = |value| Estimate {
+            value,
+            standard_error: 0.0,
+        };
+        table.push(vec![
+            exact(self.equity).into(),
+            exact(self.break_even).into(),
+            self.ev.into(),
+            self.implied.into(),
+            self.profitable.into(),
+        ]);
+        table
+    }
+}
+
+/// Seed of a report as text, so JSON readers that parse numbers as doubles
+/// don't round seeds above 2^53.
+fn seed(seed: u64) -> Value {
+    Value::Text(seed.to_string())
+}
+
+/// Equity, win and tie cells of a tally, without a standard error when exact.
+fn equity_row(tally: &Tally, exact: bool) -> [Value; 3] {
+    let equity = match exact {
+        true => Estimate {
+            value: tally.equity(),
+            standard_error: 0.0,
+        },
+        false => tally.equity_estimate(),
+    };
+    let games = tally.games();
+    [
+        equity.into(),
+        share(tally.wins, games, exact),
+        share(tally.ties, games, exact),
+    ]
+}
+
+/// Share of `count` among `samples`, as it is when they were enumerated
+/// and as an estimate with its error when they were sampled.
+fn share(count: usize, samples: usize, exact: bool) -> Value {
+    Value::from(match exact {
+        true => Estimate {
+            value: count as f64 / samples.max(1) as f64,
+            standard_error: 0.0,
+        },
+        false => Estimate::proportion(count as f64, samples),
+    })
+}
+
+/// Column or field name as a JSON or CSV key, "Hand probability" becomes
+/// "hand_probability".
+fn key(name: &str) -> String {
+    name.to_lowercase().replace([' ', '-'], "_")
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_number(x: f64) -> String {
+    match x.is_finite() {
+        true => x.to_string(),
+        false => "null".to_string(),
+    }
+}
+
+fn csv_cell(text: &str) -> String {
+    match text.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
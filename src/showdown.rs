@@ -0,0 +1,302 @@
+use crate::data::{
+    CardSet, Deck, Estimate, Low, Lowball, PokerError, Showdown, Split, Strength, Tally,
+};
+use std::cmp::Reverse;
+use std::ops::AddAssign;
+
+impl Deck {
+    /// Compares the hands of every player on a full table, panics on
+    /// invalid input.
+    pub fn showdown(table: &Deck, hands: &[Deck]) -> Showdown {
+        Self::try_showdown(table, hands).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `showdown`, but fails on a bad hand or table size, or a card
+    /// dealt twice.
+    pub fn try_showdown(table: &Deck, hands: &[Deck]) -> Result<Showdown, PokerError> {
+        Self::try_showdown_with(table, hands, |set| set.evaluate())
+    }
+
+    /// Showdown using a custom evaluator, e.g. a precomputed lookup table.
+    pub fn showdown_with<F>(table: &Deck, hands: &[Deck], evaluate: F) -> Showdown
+    where
+        F: Fn(CardSet) -> Strength,
+    {
+        Self::try_showdown_with(table, hands, evaluate).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `showdown_with`, but fails on the same input as `try_showdown`.
+    pub fn try_showdown_with<F>(
+        table: &Deck,
+        hands: &[Deck],
+        evaluate: F,
+    ) -> Result<Showdown, PokerError>
+    where
+        F: Fn(CardSet) -> Strength,
+    {
+        for hand in hands {
+            Self::check_holding(table, hand)?;
+        }
+        Self::check_unique(std::iter::once(table).chain(hands))?;
+
+        let table_set = CardSet::from(table);
+        let strengths = hands
+            .iter()
+            .map(|hand| evaluate(table_set | CardSet::from(hand)))
+            .collect();
+        Ok(Showdown::new(strengths))
+    }
+
+    /// Compares lowball hands of five to seven cards, the lowest wins.
+    pub fn lowball_showdown(hands: &[Deck], lowball: Lowball) -> Showdown {
+        Self::try_lowball_showdown(hands, lowball).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `lowball_showdown`, but fails on a bad hand size or repeated cards.
+    pub fn try_lowball_showdown(hands: &[Deck], lowball: Lowball) -> Result<Showdown, PokerError> {
+        if let Some(hand) = hands
+            .iter()
+            .find(|hand| !(5..=7).contains(&hand.cards.len()))
+        {
+            return Err(PokerError::LowballHandSize(hand.cards.len()));
+        }
+        Self::check_unique(hands)?;
+
+        let strengths = hands
+            .iter()
+            .map(|hand| CardSet::from(hand).evaluate_lowball(lowball))
+            .collect();
+        Ok(Showdown::lowball(strengths))
+    }
+}
+
+impl Showdown {
+    /// Finds the winners among the strengths of every player.
+    pub fn new(strengths: Vec<Strength>) -> Self {
+        Self::hi_lo(strengths, vec![])
+    }
+
+    /// Finds the winners of a lowball pot, whose strengths are the lowest.
+    pub fn lowball(strengths: Vec<Strength>) -> Self {
+        let lowest: Vec<_> = strengths.iter().map(Reverse).collect();
+        Showdown {
+            winners: best(&lowest),
+            strengths,
+            lows: vec![],
+            low_winners: vec![],
+        }
+    }
+
+    /// Finds the best high hands and, if anyone qualifies, the best lows.
+    pub fn hi_lo(strengths: Vec<Strength>, lows: Vec<Option<Low>>) -> Self {
+        let winners = best(&strengths);
+        let low_winners = match lows.iter().flatten().max() {
+            Some(_) => best(&lows),
+            None => vec![],
+        };
+
+        Showdown {
+            strengths,
+            winners,
+            lows,
+            low_winners,
+        }
+    }
+
+    /// Whether the high hand is split.
+    pub fn is_tie(&self) -> bool {
+        self.winners.len() > 1
+    }
+
+    /// Fraction of the pot won by `player`. With a qualifying low each half
+    /// is split on its own, so tied lows get a quarter.
+    pub fn share(&self, player: usize) -> f64 {
+        let high = split(&self.winners, player);
+        match self.low_winners.is_empty() {
+            true => high,
+            false => (high + split(&self.low_winners, player)) / 2.0,
+        }
+    }
+
+    /// Fraction of the pot won by every player.
+    pub fn shares(&self) -> Vec<f64> {
+        (0..self.strengths.len()).map(|i| self.share(i)).collect()
+    }
+}
+
+impl Tally {
+    /// Counts one showdown in which the pot share `share` was won.
+    pub fn record(&mut self, share: f64) {
+        if share >= 1.0 {
+            self.wins += 1;
+        } else if share > 0.0 {
+            self.ties += 1;
+        } else {
+            self.losses += 1;
+        }
+        self.equity += share;
+        self.equity_squares += share * share;
+    }
+
+    /// Showdowns counted.
+    pub fn games(&self) -> usize {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Fraction of showdowns won outright.
+    pub fn win(&self) -> f64 {
+        self.rate(self.wins as f64)
+    }
+
+    /// Fraction of showdowns split.
+    pub fn tie(&self) -> f64 {
+        self.rate(self.ties as f64)
+    }
+
+    /// Fraction of showdowns lost.
+    pub fn loss(&self) -> f64 {
+        self.rate(self.losses as f64)
+    }
+
+    /// Average pot share, ties counted fractionally.
+    pub fn equity(&self) -> f64 {
+        self.rate(self.equity)
+    }
+
+    /// Win rate with its standard error.
+    pub fn win_estimate(&self) -> Estimate {
+        Estimate::proportion(self.wins as f64, self.games())
+    }
+
+    /// Tie rate with its standard error.
+    pub fn tie_estimate(&self) -> Estimate {
+        Estimate::proportion(self.ties as f64, self.games())
+    }
+
+    /// Loss rate with its standard error.
+    pub fn loss_estimate(&self) -> Estimate {
+        Estimate::proportion(self.losses as f64, self.games())
+    }
+
+    /// Equity with the standard error of the pot shares. Like
+    /// `Estimate::proportion`, the error counts `Z²/2` extra lost and won
+    /// pots, so an equity that never moved in the sample isn't certain.
+    pub fn equity_estimate(&self) -> Estimate {
+        let games = self.games() as f64 + Z * Z;
+        let mean = (self.equity + Z * Z / 2.0) / games;
+        let variance = ((self.equity_squares + Z * Z / 2.0) / games - mean * mean).max(0.0);
+        Estimate {
+            value: self.equity(),
+            standard_error: (variance / games).sqrt(),
+        }
+    }
+
+    fn rate(&self, value: f64) -> f64 {
+        match self.games() {
+            0 => 0.0,
+            games => value / games as f64,
+        }
+    }
+}
+
+impl Split {
+    /// Counts the outcome of `player` in a hi-lo showdown.
+    pub fn record(&mut self, showdown: &Showdown, player: usize) {
+        let high = showdown.winners.contains(&player);
+        let low = showdown.low_winners.contains(&player);
+        if showdown.share(player) >= 1.0 {
+            self.scoops += 1;
+        } else if high && !low {
+            self.high_only += 1;
+        } else if low && !high {
+            self.low_only += 1;
+        }
+        let halves = [
+            (
+                &showdown.winners,
+                &mut self.high_alone,
+                &mut self.high_shared,
+            ),
+            (
+                &showdown.low_winners,
+                &mut self.low_alone,
+                &mut self.low_shared,
+            ),
+        ];
+        for (winners, alone, shared) in halves {
+            match split(winners, player) {
+                share if share >= 1.0 => *alone += 1,
+                share if share > 0.0 => *shared += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl AddAssign for Split {
+    fn add_assign(&mut self, other: Self) {
+        self.scoops += other.scoops;
+        self.high_only += other.high_only;
+        self.low_only += other.low_only;
+        self.high_alone += other.high_alone;
+        self.high_shared += other.high_shared;
+        self.low_alone += other.low_alone;
+        self.low_shared += other.low_shared;
+    }
+}
+
+impl AddAssign for Tally {
+    fn add_assign(&mut self, other: Self) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+        self.equity += other.equity;
+        self.equity_squares += other.equity_squares;
+    }
+}
+
+impl Estimate {
+    /// Agresti-Coull estimate of the share of `hits` among `samples`: the
+    /// share once `Z²/2` hits and misses are added, and its standard error.
+    /// Events never or always seen keep an error instead of looking certain,
+    /// and the interval is centred on the adjusted share.
+    pub fn proportion(hits: f64, samples: usize) -> Self {
+        let samples = samples as f64 + Z * Z;
+        let value = (hits + Z * Z / 2.0) / samples;
+        Estimate {
+            value,
+            standard_error: (value * (1.0 - value) / samples).sqrt(),
+        }
+    }
+
+    /// Half width of the 95% confidence interval.
+    pub fn margin(&self) -> f64 {
+        Z * self.standard_error
+    }
+
+    /// 95% confidence interval, clamped to valid probabilities.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let lower = (self.value - self.margin()).max(0.0);
+        let upper = (self.value + self.margin()).min(1.0);
+        (lower, upper)
+    }
+}
+
+/// Normal quantile of a two-sided 95% interval.
+const Z: f64 = 1.96;
+
+/// Indices of every player holding the highest value.
+pub(crate) fn best<T: Ord>(values: &[T]) -> Vec<usize> {
+    match values.iter().max() {
+        Some(best) => (0..values.len()).filter(|&i| values[i] == *best).collect(),
+        None => vec![],
+    }
+}
+
+/// Part of a pot split between `winners` that goes to `player`.
+pub(crate) fn split(winners: &[usize], player: usize) -> f64 {
+    match winners.contains(&player) {
+        true => 1.0 / winners.len() as f64,
+        false => 0.0,
+    }
+}
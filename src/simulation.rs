@@ -0,0 +1,269 @@
+use crate::data::{
+    CardSet, Estimate, Game, PokerError, Rules, Showdown, SimulationConfig, SimulationReport,
+    Strength, Tally,
+};
+use crate::showdown::{best, split};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+use std::{ops, thread};
+
+/// Batches run between two checks of an adaptive simulation.
+const ROUND_BATCHES: usize = 16;
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            players: 4,
+            iterations: 1_000_000,
+            seed: None,
+            threads: 0,
+            batch: 10_000,
+            precision: None,
+            time_budget: None,
+            rules: Rules::Standard,
+            game: Game::Holdem,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Runs the simulation with `CardSet::evaluate_with` under its rules.
+    /// Panics when the players can't all be dealt.
+    pub fn run(&self) -> SimulationReport {
+        self.try_run().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `run`, but fails without players, with Omaha hands outside four
+    /// to six cards or with more than the deck can deal.
+    pub fn try_run(&self) -> Result<SimulationReport, PokerError> {
+        self.try_run_with(|set| set.evaluate_with(self.rules))
+    }
+
+    /// Runs the simulation with a custom evaluator, e.g. a lookup table.
+    /// Cards come from the deck of the rules, the evaluator ranks them.
+    pub fn run_with<F>(&self, evaluate: F) -> SimulationReport
+    where
+        F: Fn(CardSet) -> Strength + Sync,
+    {
+        self.try_run_with(evaluate)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `run_with`, but fails on the same input as `try_run`.
+    pub fn try_run_with<F>(&self, evaluate: F) -> Result<SimulationReport, PokerError>
+    where
+        F: Fn(CardSet) -> Strength + Sync,
+    {
+        if self.players == 0 {
+            return Err(PokerError::NoPlayers);
+        }
+        if let Game::Omaha { hole_cards, .. } = self.game {
+            if !(4..=6).contains(&hole_cards) {
+                return Err(PokerError::OmahaHandSize(hole_cards));
+            }
+        }
+        let requested = self.players * self.game.hole_cards() + self.game.board_cards();
+        let left = self.rules.deck().cards.len();
+        if requested > left {
+            return Err(PokerError::NotEnoughCards { requested, left });
+        }
+
+        let timer = Instant::now();
+        let seed = self.seed.unwrap_or_else(rand::random);
+
+        let batch = |rng: &mut StdRng, iterations: usize| {
+            let mut hands = vec![Tally::default(); self.game.categories()];
+
+            for _ in 0..iterations {
+                let mut deck = self.rules.deck().shuffle_with(rng);
+
+                if self.game == Game::Badugi {
+                    let badugis: Vec<_> = (0..self.players)
+                        .map(|_| CardSet::from(&deck.deal(4)).badugi())
+                        .collect();
+                    let winners = best(&badugis);
+                    for (player, badugi) in badugis.iter().enumerate() {
+                        hands[badugi.len() - 1].record(split(&winners, player));
+                    }
+                    continue;
+                }
+
+                let table = deck.deal(self.game.board_cards());
+
+                let table = CardSet::from(&table);
+                let holes: Vec<_> = (0..self.players)
+                    .map(|_| CardSet::from(&deck.deal(self.game.hole_cards())))
+                    .collect();
+                let strengths = holes
+                    .iter()
+                    .map(|hole| self.game.evaluate_with(*hole, table, &evaluate))
+                    .collect();
+
+                let showdown = match self.game.is_hi_lo() {
+                    true => {
+                        let lows = holes.iter().map(|hole| self.game.low(*hole, table));
+                        Showdown::hi_lo(strengths, lows.collect())
+                    }
+                    false => Showdown::new(strengths),
+                };
+
+                for (player, strength) in showdown.strengths.iter().enumerate() {
+                    hands[strength.category()].record(showdown.share(player));
+                }
+            }
+
+            hands
+        };
+
+        let mut hands = vec![Tally::default(); self.game.categories()];
+        let adaptive = self.precision.is_some() || self.time_budget.is_some();
+        let round = if adaptive {
+            self.batch.max(1) * ROUND_BATCHES
+        } else {
+            self.iterations
+        };
+
+        let mut done = 0;
+        while done < self.iterations {
+            let end = self.iterations.min(done + round);
+            let merge_into = |total: &mut Vec<_>, other| merge(total, other);
+            if let Some(round) =
+                run_batches(done..end, self.batch, self.threads, seed, batch, merge_into)
+            {
+                merge(&mut hands, round);
+            }
+            done = end;
+
+            let report = SimulationReport {
+                players: self.players,
+                iterations: done,
+                seed,
+                game: self.game,
+                hands: hands.clone(),
+                elapsed: timer.elapsed(),
+            };
+            let precise = self
+                .precision
+                .is_some_and(|precision| report.max_standard_error() <= precision);
+            let out_of_time = self
+                .time_budget
+                .is_some_and(|budget| report.elapsed >= budget);
+            if precise || out_of_time {
+                break;
+            }
+        }
+
+        Ok(SimulationReport {
+            players: self.players,
+            iterations: done,
+            seed,
+            game: self.game,
+            hands,
+            elapsed: timer.elapsed(),
+        })
+    }
+}
+
+impl SimulationReport {
+    /// Chance that the pot is won with a hand of category `hand`.
+    pub fn hand_probability(&self, hand: usize) -> Estimate {
+        Estimate::proportion(self.hands[hand].equity, self.iterations)
+    }
+
+    /// Largest standard error over every probability in the report.
+    pub fn max_standard_error(&self) -> f64 {
+        self.hands
+            .iter()
+            .enumerate()
+            .flat_map(|(hand, tally)| {
+                [
+                    self.hand_probability(hand),
+                    tally.win_estimate(),
+                    tally.tie_estimate(),
+                    tally.loss_estimate(),
+                ]
+            })
+            .map(|estimate| estimate.standard_error)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Adds every tally of `other` to the matching one in `tallies`.
+pub(crate) fn merge(tallies: &mut [Tally], other: Vec<Tally>) {
+    for (tally, other) in tallies.iter_mut().zip(other) {
+        *tally += other;
+    }
+}
+
+/// Runs the `iterations` range in batches of `batch` on `threads` workers.
+/// Batch `i` covers iterations from `i * batch` and draws from its own
+/// generator seeded from `seed` and `i`. Results are merged in batch order,
+/// so the outcome doesn't depend on the threads or on how the range was cut
+/// at batch boundaries.
+pub(crate) fn run_batches<T, F, M>(
+    iterations: ops::Range<usize>,
+    batch: usize,
+    threads: usize,
+    seed: u64,
+    run: F,
+    mut merge: M,
+) -> Option<T>
+where
+    T: Send,
+    F: Fn(&mut StdRng, usize) -> T + Sync,
+    M: FnMut(&mut T, T),
+{
+    let batch = batch.max(1);
+    let batches = iterations.start / batch..iterations.end.div_ceil(batch);
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |threads| threads.get()),
+        threads => threads,
+    }
+    .clamp(1, batches.len().max(1));
+
+    let run = &run;
+    let batches = &batches;
+    let iterations = &iterations;
+    let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                scope.spawn(move || {
+                    batches
+                        .clone()
+                        .skip(worker)
+                        .step_by(threads)
+                        .map(|i| {
+                            let mut rng = StdRng::seed_from_u64(stream_seed(seed, i as u64));
+                            let start = iterations.start.max(i * batch);
+                            let end = iterations.end.min((i + 1) * batch);
+                            (i, run(&mut rng, end - start))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Simulation thread panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(i, _)| *i);
+
+    let mut results = results.into_iter().map(|(_, result)| result);
+    let mut total = results.next()?;
+    for result in results {
+        merge(&mut total, result);
+    }
+    Some(total)
+}
+
+/// Seed of stream `stream`, mixed from the master seed with SplitMix64.
+fn stream_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,203 @@
+use crate::data::{
+    Card, CardSet, Deck, Equity, EquityConfig, Face, Hand, PokerError, Showdown, Split, Strength,
+    StudHand, Tally,
+};
+use crate::equity::record;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+
+impl Face {
+    /// Faces of the seven cards of stud in the order they're dealt: two
+    /// down and one up on third street, one up on each of fourth to sixth
+    /// street and one down on seventh.
+    pub const STUD: [Face; 7] = [
+        Face::Down,
+        Face::Down,
+        Face::Up,
+        Face::Up,
+        Face::Up,
+        Face::Up,
+        Face::Down,
+    ];
+}
+
+impl StudHand {
+    /// Cards dealt, down and up.
+    pub fn len(&self) -> usize {
+        self.down.cards.len() + self.up.cards.len()
+    }
+
+    /// Whether no card was dealt.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every known card of the hand.
+    pub fn cards(&self) -> CardSet {
+        CardSet::from(&self.down) | CardSet::from(&self.up)
+    }
+
+    /// Deals `card` face down or up.
+    pub fn push(&mut self, card: Card, face: Face) {
+        match face {
+            Face::Down => self.down.cards.push(card),
+            Face::Up => self.up.cards.push(card),
+        }
+    }
+
+    /// Strength of the best five known cards. Earlier streets rank what
+    /// was dealt so far, like three cards showing pair and kicker, so only
+    /// compare hands of as many cards.
+    pub fn evaluate(&self) -> Strength {
+        self.cards().evaluate()
+    }
+
+    /// Best five known cards, `None` until five cards are dealt.
+    pub fn best_hand(&self) -> Option<Hand> {
+        (self.len() >= 5).then(|| self.cards().best_hand())
+    }
+}
+
+impl Deck {
+    /// Deals one card to every hand, panics when the deck runs out.
+    ///
+    /// ```
+    /// use poker::{Deck, Face, StudHand};
+    ///
+    /// let mut deck = Deck::french().shuffle_seeded(7);
+    /// let mut hands = vec![StudHand::default(); 3];
+    /// for face in &Face::STUD[..3] {
+    ///     deck.deal_to(&mut hands, *face);
+    /// }
+    /// assert!(hands.iter().all(|hand| hand.down.cards.len() == 2));
+    /// assert!(hands.iter().all(|hand| hand.up.cards.len() == 1));
+    /// ```
+    pub fn deal_to(&mut self, hands: &mut [StudHand], face: Face) {
+        self.try_deal_to(hands, face)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Deals one card to every hand, face up or down.
+    pub fn try_deal_to(&mut self, hands: &mut [StudHand], face: Face) -> Result<(), PokerError> {
+        let cards = self.try_deal(hands.len())?;
+        for (hand, card) in hands.iter_mut().zip(cards.cards) {
+            hand.push(card, face);
+        }
+        Ok(())
+    }
+
+    /// Deals `street` of seven-card stud, panics on invalid input.
+    pub fn deal_street(&mut self, hands: &mut [StudHand], street: usize) {
+        self.try_deal_street(hands, street)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Deals third street, three cards each, or one card of a later street
+    /// up to seventh, faced as in `Face::STUD`.
+    pub fn try_deal_street(
+        &mut self,
+        hands: &mut [StudHand],
+        street: usize,
+    ) -> Result<(), PokerError> {
+        let faces = match street {
+            3 => &Face::STUD[..3],
+            4..=7 => &Face::STUD[street - 1..street],
+            _ => return Err(PokerError::StudStreet(street)),
+        };
+        let requested = faces.len() * hands.len();
+        if requested > self.cards.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested,
+                left: self.cards.len(),
+            });
+        }
+
+        for &face in faces {
+            self.deal_to(hands, face);
+        }
+        Ok(())
+    }
+
+    /// Equity of stud hands of which only some cards are known, usually
+    /// every card of our own and the up cards of the opponents. Sampled
+    /// unless every card is known, stopping early like `Deck::equity`.
+    pub fn stud_equity(hands: &[StudHand], dead: &Deck, config: &EquityConfig) -> Equity {
+        Self::try_stud_equity(hands, dead, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like `stud_equity`, but fails on invalid input. Known cards, up
+    /// cards of opponents included, are dead to everyone. The rest of every
+    /// hand is sampled up to seven cards, unless all of them are complete.
+    pub fn try_stud_equity(
+        hands: &[StudHand],
+        dead: &Deck,
+        config: &EquityConfig,
+    ) -> Result<Equity, PokerError> {
+        if let Some(hand) = hands.iter().find(|hand| hand.len() > 7) {
+            return Err(PokerError::StudHandSize(hand.len()));
+        }
+        let decks = hands.iter().flat_map(|hand| [&hand.down, &hand.up]);
+        Self::check_unique(decks.chain([dead]))?;
+
+        let known = hands
+            .iter()
+            .fold(CardSet::from(dead), |known, hand| known | hand.cards());
+        let mut deck = config.rules.deck();
+        deck.cards.retain(|card| !known.contains(*card));
+
+        let missing: Vec<_> = hands.iter().map(|hand| 7 - hand.len()).collect();
+        let requested = missing.iter().sum();
+        if requested > deck.cards.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested,
+                left: deck.cards.len(),
+            });
+        }
+
+        let exact = requested == 0;
+        let samples = if exact { 1 } else { config.samples };
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let hi_lo = config.game.is_hi_lo();
+        let mut players = vec![Tally::default(); hands.len()];
+        let mut splits = vec![Split::default(); if hi_lo { hands.len() } else { 0 }];
+
+        let start = Instant::now();
+        let mut runouts = 0;
+        while runouts < samples && !config.stops_at(runouts, &players, start) {
+            use rand::seq::SliceRandom;
+            let (runout, _) = deck.cards.partial_shuffle(&mut rng, requested);
+            let mut rest: &[Card] = runout;
+            let sets: Vec<CardSet> = hands
+                .iter()
+                .zip(&missing)
+                .map(|(hand, &missing)| {
+                    let (dealt, left) = rest.split_at(missing);
+                    rest = left;
+                    hand.cards() | dealt.iter().copied().collect()
+                })
+                .collect();
+
+            let strengths = sets
+                .iter()
+                .map(|set| set.evaluate_with(config.rules))
+                .collect();
+            let showdown = match hi_lo {
+                true => Showdown::hi_lo(strengths, sets.iter().map(CardSet::low_eight).collect()),
+                false => Showdown::new(strengths),
+            };
+            record(&showdown, &mut players, &mut splits);
+            runouts += 1;
+        }
+
+        Ok(Equity {
+            players,
+            splits,
+            runouts,
+            exact,
+            seed,
+        })
+    }
+}
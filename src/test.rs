@@ -1,259 +1,2061 @@
-#[cfg(test)]
 // ♠ ♥ ♦ ♣
 
-mod test {
-    use crate::data::{Card, Deck, Hand};
+use crate::data::{
+    Badugi, Call, Card, CardSet, Deck, EquityConfig, Estimate, Face, Format, Game, Hand, Lowball,
+    PokerError, Range, Rank, Rules, Showdown, SimulationConfig, Split, Strength, StudHand, Suit,
+    Table, Tally, Value,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Duration;
 
-    fn card_parse(s: &str) -> Card {
-        Card::try_from(s).unwrap()
-    }
+fn card_parse(s: &str) -> Card {
+    Card::try_from(s).unwrap()
+}
 
-    #[test]
-    pub fn highest_card() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("6 ♦"),
-            card_parse("8 ♦"),
+fn deck_parse(cards: &[&str]) -> Deck {
+    Deck::from(
+        cards
+            .iter()
+            .map(|card| card_parse(card))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// `Hand` equality ignores suits, so also check the exact cards picked.
+fn assert_same_cards(hand: Hand, expected: Hand) {
+    assert_eq!(hand, expected);
+    assert_eq!(hand.cards(), expected.cards());
+}
+
+#[test]
+pub fn highest_card() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("6 ♦"),
+        card_parse("8 ♦"),
+        card_parse("10 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::HighCard(
+        card_parse("A ♣"),
+        [
+            card_parse("Q ♥"),
             card_parse("10 ♥"),
-        ]);
+            card_parse("8 ♦"),
+            card_parse("6 ♦"),
+        ],
+    );
+    assert_same_cards(hand, expected);
+}
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+#[test]
+pub fn pair() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("6 ♦"),
+        card_parse("8 ♦"),
+        card_parse("10 ♥"),
+    ]);
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::HighCard(card_parse("A ♣"));
-        assert_eq!(hand, expected);
-    }
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::Pair(
+        [card_parse("Q ♥"), card_parse("Q ♣")],
+        [card_parse("10 ♥"), card_parse("8 ♦"), card_parse("6 ♦")],
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn two_pairs() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("6 ♦"),
+        card_parse("8 ♦"),
+        card_parse("8 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::TwoPair(
+        [card_parse("Q ♥"), card_parse("Q ♣")],
+        [card_parse("8 ♥"), card_parse("8 ♦")],
+        card_parse("6 ♦"),
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn three_pairs() {
+    let table = Deck::from(vec![
+        card_parse("4 ♠"),
+        card_parse("4 ♦"),
+        card_parse("8 ♠"),
+        card_parse("8 ♦"),
+        card_parse("9 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::TwoPair(
+        [card_parse("Q ♥"), card_parse("Q ♣")],
+        [card_parse("8 ♠"), card_parse("8 ♦")],
+        card_parse("9 ♥"),
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn three_of_a_kind() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("8 ♠"),
+        card_parse("8 ♦"),
+        card_parse("8 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::ThreeOfAKind(
+        [card_parse("8 ♠"), card_parse("8 ♥"), card_parse("8 ♦")],
+        [card_parse("A ♣"), card_parse("Q ♥")],
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn straight() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♦"),
+        card_parse("5 ♦"),
+        card_parse("6 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("7 ♥"), card_parse("8 ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::Straight([
+        card_parse("4 ♦"),
+        card_parse("5 ♦"),
+        card_parse("6 ♥"),
+        card_parse("7 ♥"),
+        card_parse("8 ♣"),
+    ]);
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn straight_with_ace() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♦"),
+        card_parse("5 ♦"),
+        card_parse("7 ♥"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("8 ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::Straight([
+        card_parse("A ♣"),
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♦"),
+        card_parse("5 ♦"),
+    ]);
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn flush() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("6 ♠"),
+        card_parse("8 ♠"),
+        card_parse("10 ♠"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::Flush([
+        card_parse("2 ♠"),
+        card_parse("4 ♠"),
+        card_parse("6 ♠"),
+        card_parse("8 ♠"),
+        card_parse("10 ♠"),
+    ]);
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn full_house() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("2 ♦"),
+        card_parse("2 ♥"),
+        card_parse("8 ♠"),
+        card_parse("8 ♦"),
+    ]);
 
-    #[test]
-    pub fn pair() {
-        let table = Deck::from(vec![
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::FullHouse(
+        [card_parse("2 ♠"), card_parse("2 ♥"), card_parse("2 ♦")],
+        [card_parse("8 ♠"), card_parse("8 ♦")],
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn four_of_a_kind() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("2 ♦"),
+        card_parse("2 ♥"),
+        card_parse("2 ♣"),
+        card_parse("8 ♦"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::FourOfAKind(
+        [
             card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("6 ♦"),
-            card_parse("8 ♦"),
-            card_parse("10 ♥"),
-        ]);
+            card_parse("2 ♥"),
+            card_parse("2 ♦"),
+            card_parse("2 ♣"),
+        ],
+        card_parse("A ♣"),
+    );
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn straight_flush() {
+    let table = Deck::from(vec![
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♠"),
+        card_parse("5 ♠"),
+        card_parse("6 ♠"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::StraightFlush([
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♠"),
+        card_parse("5 ♠"),
+        card_parse("6 ♠"),
+    ]);
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn royal_flush() {
+    let table = Deck::from(vec![
+        card_parse("10 ♠"),
+        card_parse("J ♠"),
+        card_parse("Q ♠"),
+        card_parse("K ♠"),
+        card_parse("A ♠"),
+    ]);
+
+    let hand = Deck::from(vec![card_parse("9 ♠"), card_parse("8 ♠")]);
+
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::RoyalFlush([
+        card_parse("10 ♠"),
+        card_parse("J ♠"),
+        card_parse("Q ♠"),
+        card_parse("K ♠"),
+        card_parse("A ♠"),
+    ]);
+    assert_same_cards(hand, expected);
+}
+
+#[test]
+pub fn kicker_decides_pair() {
+    let table = deck_parse(&["K ♠", "K ♦", "7 ♥", "4 ♣", "2 ♠"]);
+
+    let ace_kicker = Deck::best_hand(&table, deck_parse(&["A ♥", "3 ♦"]));
+    let queen_kicker = Deck::best_hand(&table, deck_parse(&["Q ♥", "J ♦"]));
+
+    assert!(ace_kicker > queen_kicker);
+}
+
+#[test]
+pub fn suits_do_not_break_ties() {
+    let table = deck_parse(&["K ♠", "K ♦", "7 ♥", "4 ♣", "2 ♠"]);
+
+    let spades = Deck::best_hand(&table, deck_parse(&["A ♠", "9 ♠"]));
+    let clubs = Deck::best_hand(&table, deck_parse(&["A ♣", "9 ♣"]));
+
+    assert_eq!(spades, clubs);
+    assert_eq!(spades.cmp(&clubs), std::cmp::Ordering::Equal);
+}
+
+#[test]
+pub fn board_plays_for_both() {
+    let table = deck_parse(&["A ♠", "A ♦", "K ♥", "K ♣", "Q ♠"]);
+
+    let first = Deck::best_hand(&table, deck_parse(&["2 ♥", "3 ♦"]));
+    let second = Deck::best_hand(&table, deck_parse(&["4 ♣", "5 ♥"]));
+
+    assert_eq!(first, second);
+}
+
+#[test]
+pub fn wheel_is_lowest_straight() {
+    let table = deck_parse(&["2 ♠", "3 ♠", "4 ♦", "5 ♦", "9 ♥"]);
+
+    let wheel = Deck::best_hand(&table, deck_parse(&["A ♥", "K ♣"]));
+    let six_high = Deck::best_hand(&table, deck_parse(&["6 ♥", "K ♣"]));
+
+    assert!(six_high > wheel);
+}
+
+#[test]
+pub fn showdown_single_winner() {
+    let table = deck_parse(&["K ♠", "K ♦", "7 ♥", "4 ♣", "2 ♠"]);
+    let hands = [deck_parse(&["A ♥", "3 ♦"]), deck_parse(&["Q ♥", "J ♦"])];
+
+    let showdown = Deck::showdown(&table, &hands);
+
+    assert_eq!(showdown.winners, vec![0]);
+    assert!(!showdown.is_tie());
+    assert_eq!(showdown.shares(), vec![1.0, 0.0]);
+}
+
+#[test]
+pub fn showdown_split_pot() {
+    let table = deck_parse(&["A ♠", "A ♦", "K ♥", "K ♣", "Q ♠"]);
+    let hands = [
+        deck_parse(&["2 ♥", "3 ♦"]),
+        deck_parse(&["4 ♣", "5 ♥"]),
+        deck_parse(&["Q ♥", "6 ♦"]),
+    ];
+
+    let showdown = Deck::showdown(&table, &hands);
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+    assert_eq!(showdown.winners, vec![0, 1, 2]);
+    assert!(showdown.is_tie());
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::Pair([card_parse("Q ♥"), card_parse("Q ♣")]);
-        assert_eq!(hand, expected);
+    let mut tally = Tally::default();
+    for share in showdown.shares() {
+        tally.record(share);
     }
+    assert_eq!(tally.ties, 3);
+    assert!((tally.equity - 1.0).abs() < 1e-9);
+}
 
-    #[test]
-    pub fn two_pairs() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("6 ♦"),
-            card_parse("8 ♦"),
-            card_parse("8 ♥"),
-        ]);
+#[test]
+pub fn straight_with_paired_rank() {
+    let table = deck_parse(&["4 ♠", "5 ♠", "5 ♦", "6 ♦", "7 ♥"]);
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+    let hand = deck_parse(&["8 ♥", "K ♣"]);
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::TwoPair(
-            [card_parse("Q ♥"), card_parse("Q ♣")],
-            [card_parse("8 ♥"), card_parse("8 ♦")],
-        );
-        assert_eq!(hand, expected);
+    let all_hands = Deck::all_hands(&table, hand.clone());
+    let hand = Deck::best_hand(&table, hand);
+    assert_eq!(
+        usize::from(hand),
+        usize::from(Hand::Straight([Card::default(); 5]))
+    );
+    assert_eq!(all_hands[0], hand);
+}
+
+#[test]
+pub fn straight_flush_below_sixth_suited_card() {
+    let table = deck_parse(&["2 ♠", "3 ♠", "4 ♠", "5 ♠", "6 ♠"]);
+
+    let hand = deck_parse(&["K ♠", "A ♣"]);
+
+    let all_hands = Deck::all_hands(&table, hand.clone());
+    let hand = Deck::best_hand(&table, hand);
+    let expected = Hand::StraightFlush([
+        card_parse("2 ♠"),
+        card_parse("3 ♠"),
+        card_parse("4 ♠"),
+        card_parse("5 ♠"),
+        card_parse("6 ♠"),
+    ]);
+    assert_same_cards(hand, expected);
+    assert_same_cards(all_hands[0], expected);
+}
+
+#[test]
+pub fn evaluator_agrees_with_detectors() {
+    for table_size in [3, 4, 5] {
+        for seed in 0..5_000 {
+            let mut deck = Deck::french().shuffle_seeded(seed);
+            let table = deck.deal(table_size);
+            let hand = deck.deal(2);
+
+            let set = CardSet::from(&table) | CardSet::from(&hand);
+            let detected = Deck::all_hands(&table, hand.clone())[0];
+            let best = Deck::best_hand(&table, hand);
+
+            assert_eq!(best, detected, "{} vs {}", best, detected);
+            assert_eq!(set.evaluate(), detected.strength());
+        }
     }
+}
 
-    #[test]
-    pub fn three_pairs() {
-        let table = Deck::from(vec![
-            card_parse("4 ♠"),
-            card_parse("4 ♦"),
-            card_parse("8 ♠"),
-            card_parse("8 ♦"),
-            card_parse("9 ♥"),
-        ]);
+#[cfg(feature = "lookup")]
+#[test]
+pub fn lookup_table_agrees_with_best_hand() {
+    use crate::data::LookupTable;
+    use itertools::Itertools;
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("Q ♣")]);
+    let lookup = LookupTable::build();
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::TwoPair(
-            [card_parse("Q ♥"), card_parse("Q ♣")],
-            [card_parse("8 ♠"), card_parse("8 ♦")],
-        );
-        assert_eq!(hand, expected);
+    let path = std::env::temp_dir().join(format!("poker-lookup-{}.bin", std::process::id()));
+    lookup.save(&path).unwrap();
+    let loaded = LookupTable::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, lookup);
+
+    for seed in 0..3 {
+        let sample = Deck::french().shuffle_seeded(seed).deal(12);
+        for cards in sample.cards.into_iter().combinations(7) {
+            let mut table = Deck::from(cards);
+            let hand = table.deal(2);
+            let set = CardSet::from(&table) | CardSet::from(&hand);
+            let best = Deck::best_hand(&table, hand);
+            assert_eq!(loaded.evaluate(set), best.strength(), "{}", best);
+        }
     }
 
-    #[test]
-    pub fn three_of_a_kind() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("8 ♠"),
-            card_parse("8 ♦"),
-            card_parse("8 ♥"),
-        ]);
+    let mut table = deck_parse(&["2 ♠", "3 ♠", "4 ♠", "5 ♠", "9 ♥"]);
+    let hand = table.deal(2);
+    let set = CardSet::from(&table) | CardSet::from(&hand);
+    assert_eq!(lookup.evaluate(set), set.evaluate());
+}
+
+#[test]
+pub fn equity_exact_on_the_turn() {
+    let hands = [deck_parse(&["A ♠", "A ♥"]), deck_parse(&["K ♠", "K ♥"])];
+    let board = deck_parse(&["2 ♣", "7 ♦", "9 ♣", "J ♠"]);
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+    let equity = Deck::equity(
+        &hands,
+        &board,
+        &Deck::from(vec![]),
+        &EquityConfig::default(),
+    );
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected =
-            Hand::ThreeOfAKind([card_parse("8 ♠"), card_parse("8 ♥"), card_parse("8 ♦")]);
-        assert_eq!(hand, expected);
+    assert!(equity.exact);
+    assert_eq!(equity.runouts, 44);
+    assert_eq!(equity.players[0].wins, 42);
+    assert_eq!(equity.players[1].wins, 2);
+    assert_eq!(equity.players[1].ties, 0);
+}
+
+#[test]
+pub fn equity_dead_cards_and_ties() {
+    let hands = [deck_parse(&["2 ♠", "3 ♥"]), deck_parse(&["4 ♠", "5 ♥"])];
+    let board = deck_parse(&["A ♠", "K ♦", "Q ♣", "J ♠"]);
+    let dead = deck_parse(&["10 ♠", "10 ♥", "10 ♦"]);
+
+    let equity = Deck::equity(&hands, &board, &dead, &EquityConfig::default());
+
+    assert_eq!(equity.runouts, 41);
+    // Only pairing a hole card breaks the tie of playing the board
+    assert_eq!(equity.players[0].wins, 6);
+    assert_eq!(equity.players[1].wins, 6);
+    assert_eq!(equity.players[0].ties, 29);
+    assert!((equity.players[0].equity() + equity.players[1].equity() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+pub fn equity_falls_back_to_sampling() {
+    let hands = [deck_parse(&["A ♠", "A ♥"]), deck_parse(&["7 ♣", "2 ♦"])];
+    let config = EquityConfig {
+        max_runouts: 1_000,
+        samples: 2_000,
+        seed: Some(7),
+        ..EquityConfig::default()
+    };
+
+    let equity = Deck::equity(&hands, &Deck::from(vec![]), &Deck::from(vec![]), &config);
+
+    assert!(!equity.exact);
+    assert_eq!(equity.players[0].games(), 2_000);
+    assert!(equity.players[0].equity() > 0.75);
+}
+
+#[test]
+pub fn range_parse_expands_shorthand() {
+    let range = Range::parse("22+, A2s+, KTo+, QJs").unwrap();
+    assert_eq!(range.len(), 78 + 48 + 36 + 4);
+
+    assert_eq!(Range::parse("A2s-A5s").unwrap().len(), 16);
+    assert_eq!(Range::parse("99-66").unwrap().len(), 24);
+    assert_eq!(Range::parse("KT+").unwrap().len(), 48);
+    assert_eq!(Range::parse("AhKh").unwrap().len(), 1);
+
+    for invalid in ["AKx", "AKs:2", "Z2", "AA-KQs", "AhAh", "A5s-K2s"] {
+        assert_eq!(Range::parse(invalid), None, "{}", invalid);
     }
+}
 
-    #[test]
-    pub fn straight() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("3 ♠"),
-            card_parse("4 ♦"),
-            card_parse("5 ♦"),
-            card_parse("6 ♥"),
-        ]);
-
-        let hand = Deck::from(vec![card_parse("7 ♥"), card_parse("8 ♣")]);
-
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::Straight([
-            card_parse("4 ♦"),
-            card_parse("5 ♦"),
-            card_parse("6 ♥"),
-            card_parse("7 ♥"),
-            card_parse("8 ♣"),
-        ]);
-        assert_eq!(hand, expected);
+#[test]
+pub fn range_weights_and_blockers() {
+    let mut range = Range::parse("AA, AKs:0.5").unwrap();
+    assert!((range.total_weight() - 8.0).abs() < 1e-9);
+
+    range.remove_blocked(&deck_parse(&["A ♠"]));
+    assert_eq!(range.len(), 3 + 3);
+    assert_eq!(
+        range.to_string(),
+        "AdAh, AcAh, AcAd, AhKh:0.5, AdKd:0.5, AcKc:0.5"
+    );
+}
+
+#[test]
+pub fn range_display_is_compact() {
+    for notation in [
+        "22+, A2s+, KTo+, QJs",
+        "JJ-55, AK, A9s-A6s, KQo:0.25",
+        "AA, KK:0.5, K2s+",
+    ] {
+        let range = Range::parse(notation).unwrap();
+        assert_eq!(range.to_string(), notation);
     }
+}
 
-    #[test]
-    pub fn straight_with_ace() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("3 ♠"),
-            card_parse("4 ♦"),
-            card_parse("5 ♦"),
-            card_parse("7 ♥"),
-        ]);
+#[test]
+pub fn range_equity_of_single_hands_is_exact() {
+    let ranges = [
+        Range::from([card_parse("A ♠"), card_parse("A ♥")]),
+        Range::from([card_parse("K ♠"), card_parse("K ♥")]),
+    ];
+    let board = deck_parse(&["2 ♣", "7 ♦", "9 ♣", "J ♠"]);
 
-        let hand = Deck::from(vec![card_parse("8 ♥"), card_parse("A ♣")]);
+    let equity = Range::equity(
+        &ranges,
+        &board,
+        &Deck::from(vec![]),
+        &EquityConfig::default(),
+    );
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::Straight([
-            card_parse("A ♣"),
-            card_parse("2 ♠"),
-            card_parse("3 ♠"),
-            card_parse("4 ♦"),
-            card_parse("5 ♦"),
-        ]);
-        assert_eq!(hand, expected);
+    assert!(equity.exact);
+    assert_eq!(equity.players[0].wins, 42);
+    assert_eq!(equity.combos[1][0].1.wins, 2);
+}
+
+#[test]
+pub fn range_equity_removes_cards_held_by_others() {
+    let ranges = [Range::parse("AA").unwrap(), Range::parse("AsAh").unwrap()];
+    let board = deck_parse(&["2 ♣", "7 ♦", "9 ♥", "J ♣", "Q ♠"]);
+    let config = EquityConfig {
+        samples: 500,
+        seed: Some(1),
+        ..EquityConfig::default()
+    };
+
+    let equity = Range::equity(&ranges, &board, &Deck::from(vec![]), &config);
+
+    assert!(!equity.exact);
+    assert_eq!(equity.players[0].ties, 500);
+    assert_eq!(equity.combos[0].len(), 1);
+    assert_eq!(
+        equity.combos[0][0].0.cards,
+        [card_parse("A ♣"), card_parse("A ♦")]
+    );
+}
+
+#[test]
+pub fn range_equity_follows_weights() {
+    let ranges = [
+        Range::parse("AA:0.25, KK").unwrap(),
+        Range::parse("QQ").unwrap(),
+    ];
+    let board = deck_parse(&["2 ♣", "7 ♦", "9 ♥", "J ♣", "3 ♠"]);
+    let config = EquityConfig {
+        samples: 4_000,
+        seed: Some(2),
+        ..EquityConfig::default()
+    };
+
+    let equity = Range::equity(&ranges, &board, &Deck::from(vec![]), &config);
+
+    let aces: usize = equity.combos[0]
+        .iter()
+        .filter(|(combo, _)| combo.cards[0].rank == Rank::Ace)
+        .map(|(_, tally)| tally.games())
+        .sum();
+    // 1.5 of 7.5 weighted combos are aces
+    assert!((600..1_000).contains(&aces), "{}", aces);
+    assert_eq!(equity.players[0].wins, 4_000);
+
+    // Each player row is followed by one row per combo
+    let table = equity.table();
+    assert_eq!(table.rows.len(), 2 + 12 + 6);
+    assert_eq!(table.rows[0][1], "AA:0.25, KK".into());
+    let (combo, tally) = &equity.combos[0][0];
+    let cards = format!("{:#}", Deck::from(combo.cards.to_vec()));
+    assert_eq!(table.rows[1][..2], [1usize.into(), cards.into()]);
+    let win = Estimate::proportion(tally.wins as f64, tally.games());
+    assert_eq!(table.rows[1][3], Value::from(win));
+    assert_eq!(table.rows[13][0], 2usize.into());
+    assert_eq!(table.rows[13][1], "QQ".into());
+}
+
+#[test]
+pub fn seeded_shuffle_and_deal_repeat() {
+    assert_eq!(
+        Deck::french().shuffle_seeded(42).cards,
+        Deck::french().shuffle_seeded(42).cards
+    );
+    assert_ne!(
+        Deck::french().shuffle_seeded(42).cards,
+        Deck::french().shuffle_seeded(43).cards
+    );
+
+    let deal = |seed| {
+        let mut deck = Deck::french();
+        let hand = deck.deal_with(2, &mut StdRng::seed_from_u64(seed));
+        (hand.cards, deck.cards.len())
+    };
+    assert_eq!(deal(9), deal(9));
+    assert_eq!(deal(9).1, 50);
+}
+
+#[test]
+pub fn seeded_simulation_repeats() {
+    let config = SimulationConfig {
+        players: 3,
+        iterations: 2_000,
+        seed: Some(42),
+        threads: 1,
+        batch: 500,
+        ..SimulationConfig::default()
+    };
+
+    let first = config.run();
+    let second = config.run();
+
+    assert_eq!(first.seed, 42);
+    assert_eq!(first.hands, second.hands);
+
+    let unseeded = SimulationConfig {
+        seed: None,
+        ..config
     }
+    .run();
+    let replay = SimulationConfig {
+        seed: Some(unseeded.seed),
+        ..config
+    }
+    .run();
+    assert_eq!(unseeded.hands, replay.hands);
+}
 
-    #[test]
-    pub fn flush() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("6 ♠"),
-            card_parse("8 ♠"),
-            card_parse("10 ♠"),
-        ]);
+#[test]
+pub fn seeded_equity_sampling_repeats() {
+    let ranges = [
+        Range::parse("22+").unwrap(),
+        Range::parse("AKs, AKo").unwrap(),
+    ];
+    let config = EquityConfig {
+        samples: 1_000,
+        seed: Some(5),
+        ..EquityConfig::default()
+    };
+    let empty = Deck::from(vec![]);
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+    let first = Range::equity(&ranges, &empty, &empty, &config);
+    let second = Range::equity(&ranges, &empty, &empty, &config);
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::Flush([
-            card_parse("2 ♠"),
-            card_parse("4 ♠"),
-            card_parse("6 ♠"),
-            card_parse("8 ♠"),
-            card_parse("10 ♠"),
-        ]);
-        assert_eq!(hand, expected);
+    assert_eq!(first.players, second.players);
+    assert_eq!(first.seed, 5);
+}
+
+#[test]
+pub fn simulation_does_not_depend_on_threads() {
+    let config = SimulationConfig {
+        players: 5,
+        iterations: 3_500,
+        seed: Some(11),
+        threads: 1,
+        batch: 400,
+        ..SimulationConfig::default()
+    };
+
+    let single = config.run();
+    for threads in [2, 3, 16] {
+        let report = SimulationConfig { threads, ..config }.run();
+        assert_eq!(report.hands, single.hands, "{} threads", threads);
     }
 
-    #[test]
-    pub fn full_house() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("2 ♦"),
-            card_parse("2 ♥"),
-            card_parse("8 ♠"),
-            card_parse("8 ♦"),
-        ]);
+    let games: usize = single.hands.iter().map(|tally| tally.games()).sum();
+    assert_eq!(games, 5 * 3_500);
+}
+
+#[test]
+pub fn estimate_intervals() {
+    let half = Estimate::proportion(500.0, 1_000);
+    assert_eq!(half.value, 0.5);
+    assert!((half.standard_error - 0.0158).abs() < 1e-3);
+    let (lower, upper) = half.confidence_interval();
+    assert!(lower < 0.5 && 0.5 < upper);
+
+    // The interval is centred on the adjusted share it reports
+    let rare = Estimate::proportion(30.0, 1_000);
+    let adjusted = (30.0 + 1.96 * 1.96 / 2.0) / (1_000.0 + 1.96 * 1.96);
+    assert!((rare.value - adjusted).abs() < 1e-12);
+    let (lower, upper) = rare.confidence_interval();
+    assert!(((lower + upper) / 2.0 - rare.value).abs() < 1e-12);
+
+    // Never or always seen is not the same as certain
+    let unseen = Estimate::proportion(0.0, 1_000);
+    assert!(unseen.value > 0.0 && unseen.standard_error > 0.0);
+    assert_eq!(unseen.confidence_interval().0, 0.0);
+    let always = Estimate::proportion(30.0, 30);
+    assert!(always.value < 1.0 && always.margin() > 0.05);
+    assert!(Value::from(always).to_string().contains('±'));
+
+    let mut tally = Tally::default();
+    for share in [1.0, 0.0, 0.5, 0.5] {
+        tally.record(share);
+    }
+    let equity = tally.equity_estimate();
+    assert_eq!(equity.value, 0.5);
+    let games = 4.0 + 1.96f64.powi(2);
+    let variance = (1.5 + 1.96f64.powi(2) / 2.0) / games - 0.25;
+    assert!((equity.standard_error - (variance / games).sqrt()).abs() < 1e-9);
+
+    // Pots always won keep an error, so they don't print as exact
+    let mut tally = Tally::default();
+    for _ in 0..30 {
+        tally.record(1.0);
+    }
+    let equity = tally.equity_estimate();
+    assert_eq!(equity.value, 1.0);
+    assert!(equity.standard_error > 0.0);
+    assert!(Value::from(equity).to_string().contains('±'));
+}
+
+#[test]
+pub fn simulation_stops_at_precision_or_time() {
+    let config = SimulationConfig {
+        players: 2,
+        iterations: 1_000_000_000,
+        seed: Some(3),
+        threads: 2,
+        batch: 100,
+        ..SimulationConfig::default()
+    };
+
+    let coarse = SimulationConfig {
+        precision: Some(1.0),
+        ..config
+    }
+    .run();
+    assert_eq!(coarse.iterations, 1_600);
+
+    let timed = SimulationConfig {
+        precision: Some(1e-6),
+        time_budget: Some(Duration::from_millis(200)),
+        ..config
+    }
+    .run();
+    assert!(timed.iterations < config.iterations);
+    assert!(timed.max_standard_error() > 1e-6);
+}
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+#[test]
+pub fn equity_sampling_stops_at_precision_or_time() {
+    let config = EquityConfig {
+        max_runouts: 0,
+        samples: 100_000_000,
+        seed: Some(3),
+        precision: Some(0.01),
+        ..EquityConfig::default()
+    };
+    let hands = [Deck::parse("AhKh").unwrap(), Deck::parse("QsQd").unwrap()];
+    let none = Deck::from(vec![]);
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::FullHouse(
-            [card_parse("2 ♠"), card_parse("2 ♥"), card_parse("2 ♦")],
-            [card_parse("8 ♠"), card_parse("8 ♦")],
+    let equity = Deck::equity(&hands, &none, &none, &config);
+    assert!(!equity.exact);
+    assert_eq!(equity.runouts % 1_000, 0);
+    assert!(equity.runouts <= 3_000);
+    assert!(equity
+        .players
+        .iter()
+        .all(|tally| tally.equity_estimate().standard_error <= 0.01));
+
+    let timed = EquityConfig {
+        precision: Some(1e-9),
+        time_budget: Some(Duration::from_millis(100)),
+        ..config
+    };
+    let equity = Deck::equity(&hands, &none, &none, &timed);
+    assert!(equity.runouts < timed.samples);
+
+    let ranges = [Range::parse("QQ+").unwrap(), Range::parse("AKs").unwrap()];
+    let equity = Range::equity(&ranges, &none, &none, &config);
+    assert!(equity.players[0].games() <= 3_000);
+
+    let hands = [
+        StudHand::parse("AsAd/Ah").unwrap(),
+        StudHand::parse("/QhQs").unwrap(),
+    ];
+    let equity = Deck::stud_equity(&hands, &none, &config);
+    assert!(equity.runouts <= 3_000);
+    assert_eq!(equity.players[0].games(), equity.runouts);
+}
+
+#[test]
+pub fn simulation_rejects_deals_the_deck_cannot_cover() {
+    let config = SimulationConfig {
+        iterations: 10,
+        ..SimulationConfig::default()
+    };
+    let with = |players, rules, game| SimulationConfig {
+        players,
+        rules,
+        game,
+        ..config
+    };
+    let short = Rules::ShortDeck {
+        trips_beat_straight: false,
+    };
+    let omaha = Game::Omaha {
+        hole_cards: 6,
+        hi_lo: false,
+    };
+    let stud = Game::Stud { hi_lo: false };
+
+    assert_eq!(
+        with(0, Rules::Standard, Game::Holdem)
+            .try_run()
+            .unwrap_err(),
+        PokerError::NoPlayers
+    );
+    let too_many = [
+        (with(30, Rules::Standard, Game::Holdem), 65, 52),
+        (with(17, short, Game::Holdem), 39, 36),
+        (with(8, Rules::Standard, stud), 56, 52),
+        (with(8, Rules::Standard, omaha), 53, 52),
+    ];
+    for (config, requested, left) in too_many {
+        assert_eq!(
+            config.try_run().unwrap_err(),
+            PokerError::NotEnoughCards { requested, left }
         );
-        assert_eq!(hand, expected);
     }
+    for hole_cards in [0, 2, 7] {
+        let omaha = Game::Omaha {
+            hole_cards,
+            hi_lo: false,
+        };
+        assert_eq!(
+            with(2, Rules::Standard, omaha).try_run().unwrap_err(),
+            PokerError::OmahaHandSize(hole_cards)
+        );
+    }
+    assert!(with(23, Rules::Standard, Game::Holdem).try_run().is_ok());
+    assert!(with(7, Rules::Standard, stud).try_run().is_ok());
+}
 
-    #[test]
-    pub fn four_of_a_kind() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("2 ♦"),
-            card_parse("2 ♥"),
-            card_parse("2 ♣"),
-            card_parse("8 ♦"),
-        ]);
+#[test]
+pub fn evaluation_errors() {
+    let table = deck_parse(&["A ♠", "A ♦", "K ♥"]);
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+    assert_eq!(
+        Deck::try_best_hand(&table, deck_parse(&["2 ♥"])),
+        Err(PokerError::HandSize(1))
+    );
+    assert_eq!(
+        Deck::try_all_hands(&deck_parse(&["A ♠", "A ♦"]), deck_parse(&["2 ♥", "3 ♥"])),
+        Err(PokerError::TableSize(2))
+    );
+    assert_eq!(
+        Deck::try_best_hand(&table, deck_parse(&["A ♠", "3 ♥"])),
+        Err(PokerError::DuplicateCard(card_parse("A ♠")))
+    );
+    assert!(Deck::try_best_hand(&table, deck_parse(&["A ♥", "A ♣"])).is_ok());
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::FourOfAKind([
-            card_parse("2 ♠"),
-            card_parse("2 ♥"),
-            card_parse("2 ♦"),
-            card_parse("2 ♣"),
-        ]);
-        assert_eq!(hand, expected);
+    let hands = [deck_parse(&["Q ♥", "J ♦"]), deck_parse(&["Q ♥", "3 ♦"])];
+    assert_eq!(
+        Deck::try_showdown(&table, &hands).unwrap_err(),
+        PokerError::DuplicateCard(card_parse("Q ♥"))
+    );
+    assert_eq!(
+        Deck::try_showdown(&table, &[deck_parse(&["Q ♥"])]).unwrap_err(),
+        PokerError::HandSize(1)
+    );
+    assert_eq!(
+        Deck::try_showdown(&deck_parse(&["A ♠", "A ♦"]), &hands[..1]).unwrap_err(),
+        PokerError::TableSize(2)
+    );
+    assert_eq!(
+        Deck::try_showdown(&table, &hands[..1]).unwrap().winners,
+        vec![0]
+    );
+}
+
+#[test]
+pub fn deal_and_parse_errors() {
+    let mut deck = deck_parse(&["A ♠", "A ♦", "K ♥"]);
+    assert_eq!(
+        deck.try_deal(4).unwrap_err(),
+        PokerError::NotEnoughCards {
+            requested: 4,
+            left: 3
+        }
+    );
+    assert_eq!(deck.try_deal(3).unwrap().cards.len(), 3);
+    assert_eq!(deck.try_deal(0).unwrap().cards.len(), 0);
+
+    assert_eq!(
+        Card::parse("1 ♠"),
+        Err(PokerError::UnknownRank {
+            input: "1 ♠".to_string(),
+            position: 0
+        })
+    );
+    assert_eq!(
+        PokerError::DuplicateCard(card_parse("Q ♥")).to_string(),
+        "Card Q ♥ is used more than once"
+    );
+}
+
+#[test]
+#[should_panic(expected = "Card 7 ♣ is used more than once")]
+pub fn best_hand_rejects_duplicates() {
+    let table = deck_parse(&["7 ♣", "7 ♦", "K ♥"]);
+    Deck::best_hand(&table, deck_parse(&["7 ♣", "7 ♥"]));
+}
+
+#[test]
+pub fn parse_shorthand_notation() {
+    let ten = Card::new(Rank::Ten, Suit::Spades);
+    for notation in ["10 ♠", "10♠", "Ts", "ts", "10s", "T ♠", "tS"] {
+        assert_eq!(Card::parse(notation), Ok(ten), "{}", notation);
     }
 
-    #[test]
-    pub fn straight_flush() {
-        let table = Deck::from(vec![
-            card_parse("2 ♠"),
-            card_parse("3 ♠"),
-            card_parse("4 ♠"),
-            card_parse("5 ♠"),
-            card_parse("6 ♠"),
-        ]);
+    let deck = Deck::parse("AhKd7c").unwrap();
+    assert_eq!(deck.cards, deck_parse(&["A ♥", "K ♦", "7 ♣"]).cards);
+    assert_eq!(Deck::parse("ah, KD 7♣").unwrap().cards, deck.cards);
+    assert_eq!(Deck::parse("A ♥ K ♦ 7 ♣").unwrap().cards, deck.cards);
+    assert_eq!(Deck::parse("10d10c").unwrap().cards.len(), 2);
+    assert!(Deck::parse("  ").unwrap().cards.is_empty());
+}
 
-        let hand = Deck::from(vec![card_parse("Q ♥"), card_parse("A ♣")]);
+#[test]
+pub fn parse_errors_point_at_the_problem() {
+    assert_eq!(
+        Deck::parse("AhXd").unwrap_err(),
+        PokerError::UnknownRank {
+            input: "AhXd".to_string(),
+            position: 2
+        }
+    );
+    assert_eq!(
+        Deck::parse("AhKx").unwrap_err(),
+        PokerError::UnknownSuit {
+            input: "AhKx".to_string(),
+            position: 3
+        }
+    );
+    assert_eq!(
+        Deck::parse("AhK").unwrap_err(),
+        PokerError::MissingSuit {
+            input: "AhK".to_string()
+        }
+    );
+    assert_eq!(
+        Card::parse("AhKd"),
+        Err(PokerError::CardCount {
+            input: "AhKd".to_string(),
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Deck::parse("AhKx").unwrap_err().to_string(),
+        "Unknown suit at position 3 of \"AhKx\", expected s, h, d, c, ♠, ♥, ♦ or ♣"
+    );
+}
 
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::StraightFlush([
-            card_parse("2 ♠"),
-            card_parse("3 ♠"),
-            card_parse("4 ♠"),
-            card_parse("5 ♠"),
-            card_parse("6 ♠"),
-        ]);
-        assert_eq!(hand, expected);
+#[test]
+pub fn ascii_display_round_trips() {
+    for card in Deck::french().cards {
+        assert_eq!(Card::parse(&format!("{:#}", card)), Ok(card));
+        assert_eq!(Card::parse(&format!("{}", card)), Ok(card));
+    }
+
+    let deck = Deck::parse("Td 2c As").unwrap();
+    assert_eq!(format!("{:#}", deck), "Td2cAs");
+    assert_eq!(format!("{}", deck), "10 ♦ 2 ♣ A ♠");
+    assert_eq!(
+        Deck::parse(&format!("{:#}", deck)).unwrap().cards,
+        deck.cards
+    );
+
+    let hand = Deck::best_hand(
+        &deck_parse(&["A ♠", "A ♦", "K ♥"]),
+        deck_parse(&["2 ♥", "3 ♥"]),
+    );
+    assert_eq!(format!("{:#}", hand), "Pair(As Ad Kh 3h 2h)");
+}
+
+#[test]
+pub fn from_str_for_card_types() {
+    assert_eq!("10".parse::<Rank>(), Ok(Rank::Ten));
+    assert_eq!(Rank::try_from("q"), Ok(Rank::Queen));
+    assert!("11".parse::<Rank>().is_err());
+    assert_eq!("♦".parse::<Suit>(), Ok(Suit::Diamonds));
+    assert_eq!(Suit::try_from("C"), Ok(Suit::Clubs));
+    assert!("x".parse::<Suit>().is_err());
+
+    assert_eq!("Qh".parse::<Card>(), Ok(card_parse("Q ♥")));
+    assert_eq!(Card::try_from("Q ♥"), Ok(card_parse("Q ♥")));
+    assert_eq!(Deck::try_from("Qh 2c").unwrap().cards.len(), 2);
+}
+
+#[test]
+pub fn hand_display_round_trips() {
+    let hand: Hand = "FullHouse(K ♠ K ♥ K ♦ 3 ♣ 3 ♥)".parse().unwrap();
+    assert_same_cards(
+        hand,
+        Hand::FullHouse(
+            [card_parse("K ♠"), card_parse("K ♥"), card_parse("K ♦")],
+            [card_parse("3 ♣"), card_parse("3 ♥")],
+        ),
+    );
+
+    for seed in 0..200 {
+        let mut deck = Deck::french().shuffle_seeded(seed);
+        let table = deck.deal(5);
+        let hand = Deck::best_hand(&table, deck.deal(2));
+
+        for logged in [format!("{}", hand), format!("{:#}", hand)] {
+            let parsed = Hand::try_from(logged.as_str()).unwrap();
+            assert_same_cards(parsed, hand);
+        }
+    }
+}
+
+#[test]
+pub fn hand_parse_errors() {
+    let invalid = |s: &str| Err(PokerError::InvalidHand(s.to_string()));
+
+    assert_eq!(
+        "Pair(As Ad Kh 3h 2h".parse::<Hand>(),
+        invalid("Pair(As Ad Kh 3h 2h")
+    );
+    assert_eq!(
+        "Trips(As Ad Ah 3h 2h)".parse::<Hand>(),
+        invalid("Trips(As Ad Ah 3h 2h)")
+    );
+    // Cards that make a different hand, or kickers ahead of the pair
+    assert_eq!(
+        "Flush(As Ad Kh 3h 2h)".parse::<Hand>(),
+        invalid("Flush(As Ad Kh 3h 2h)")
+    );
+    assert_eq!(
+        "Pair(Kh As Ad 3h 2h)".parse::<Hand>(),
+        invalid("Pair(Kh As Ad 3h 2h)")
+    );
+
+    assert_eq!(
+        "Pair(As Ad Kh 3h)".parse::<Hand>(),
+        Err(PokerError::CardCount {
+            input: "Pair(As Ad Kh 3h)".to_string(),
+            expected: 5,
+            found: 4
+        })
+    );
+    assert_eq!(
+        "Pair(As As Kh 3h 2h)".parse::<Hand>(),
+        Err(PokerError::DuplicateCard(card_parse("A ♠")))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn serde_round_trips() {
+    let card = card_parse("A ♥");
+    assert_eq!(serde_json::to_string(&card).unwrap(), "\"Ah\"");
+    assert_eq!(
+        serde_json::from_str::<Card>("\"10d\"").unwrap(),
+        card_parse("10 ♦")
+    );
+    assert!(serde_json::from_str::<Card>("\"Ax\"").is_err());
+
+    let deck = Deck::parse("AhKd").unwrap();
+    assert_eq!(
+        serde_json::to_string(&deck).unwrap(),
+        "{\"cards\":[\"Ah\",\"Kd\"]}"
+    );
+
+    let hand: Hand = "Pair(As Ad Kh 3h 2h)".parse().unwrap();
+    let json = serde_json::to_string(&hand).unwrap();
+    assert_eq!(json, "\"Pair(As Ad Kh 3h 2h)\"");
+    assert_same_cards(serde_json::from_str(&json).unwrap(), hand);
+    assert!(serde_json::from_str::<Hand>("\"Flush(As Ad Kh 3h 2h)\"").is_err());
+
+    let wheel: Hand = "Straight(Ah 6s 7d 8c 9s)".parse().unwrap();
+    let json = serde_json::to_string(&wheel).unwrap();
+    assert_eq!(json, "\"Straight(Ah 6s 7d 8c 9s)\"");
+    assert_same_cards(serde_json::from_str(&json).unwrap(), wheel);
+
+    let range = Range::parse("QQ+, AKs:0.5").unwrap();
+    let json = serde_json::to_string(&range).unwrap();
+    assert_eq!(serde_json::from_str::<Range>(&json).unwrap(), range);
+
+    let config = SimulationConfig {
+        players: 3,
+        iterations: 500,
+        seed: Some(7),
+        threads: 1,
+        batch: 100,
+        ..SimulationConfig::default()
+    };
+    let report = config.run();
+    let json = serde_json::to_string(&report).unwrap();
+    let loaded: crate::data::SimulationReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.seed, 7);
+    assert_eq!(loaded.hands, report.hands);
+    assert_eq!(loaded.elapsed, report.elapsed);
+}
+
+#[test]
+pub fn equity_errors() {
+    let config = EquityConfig::default();
+    let none = Deck::from(vec![]);
+    let hands = [Deck::parse("AhKh").unwrap(), Deck::parse("AhQd").unwrap()];
+    assert_eq!(
+        Deck::try_equity(&hands, &none, &none, &config).unwrap_err(),
+        PokerError::DuplicateCard(card_parse("A ♥"))
+    );
+
+    // Twenty four hands leave four cards for a board of five
+    let mut deck = Deck::french();
+    let hands: Vec<_> = (0..24).map(|_| deck.deal(2)).collect();
+    assert_eq!(
+        Deck::try_equity(&hands, &none, &none, &config).unwrap_err(),
+        PokerError::NotEnoughCards {
+            requested: 5,
+            left: 4
+        }
+    );
+
+    let ranges = [Range::parse("AhKh").unwrap(), Range::parse("AA").unwrap()];
+    let board = Deck::parse("AdAsAc").unwrap();
+    assert_eq!(
+        Range::try_equity(&ranges, &board, &none, &config).unwrap_err(),
+        PokerError::EmptyRange(1)
+    );
+
+    let board = Deck::parse("2c3c4c5c6c7c").unwrap();
+    assert_eq!(
+        Range::try_equity(&ranges, &board, &none, &config).unwrap_err(),
+        PokerError::BoardSize(6)
+    );
+    // Every combo of the second range shares a card with the first
+    let ranges = [
+        Range::parse("AsAh").unwrap(),
+        Range::parse("AsAh,AsAd").unwrap(),
+    ];
+    assert_eq!(
+        Range::try_equity(&ranges, &none, &none, &config).unwrap_err(),
+        PokerError::RangesConflict
+    );
+    let ranges = [
+        Range::parse("AA").unwrap(),
+        Range::parse("AA").unwrap(),
+        Range::parse("AA").unwrap(),
+    ];
+    assert_eq!(
+        Range::try_equity(&ranges, &none, &none, &config).unwrap_err(),
+        PokerError::RangesConflict
+    );
+
+    // Hands and ranges that leave too few cards to finish the board
+    let mut deck = Deck::french();
+    deck.cards
+        .retain(|card| card.rank != Rank::King && card.rank != Rank::Ace);
+    let mut ranges: Vec<_> = deck
+        .cards
+        .chunks(2)
+        .map(|cards| Range::from([cards[0], cards[1]]))
+        .collect();
+    ranges.push(Range::parse("KsKh").unwrap());
+    ranges.push(Range::parse("KdKc,AsAh").unwrap());
+    assert_eq!(
+        Range::try_equity(&ranges, &none, &none, &config).unwrap_err(),
+        PokerError::NotEnoughCards {
+            requested: 53,
+            left: 52
+        }
+    );
+
+    let omaha = EquityConfig {
+        game: Game::Omaha {
+            hole_cards: 4,
+            hi_lo: false,
+        },
+        ..config
+    };
+    assert_eq!(
+        Range::try_equity(&ranges, &none, &none, &omaha).unwrap_err(),
+        PokerError::UnsupportedGame(omaha.game)
+    );
+}
+
+#[test]
+pub fn table_renders_every_format() {
+    let mut table = Table::new(&["Hand", "Win"]).field("Seed", 7u64);
+    table.push(vec![
+        "AhKh".into(),
+        Estimate {
+            value: 0.25,
+            standard_error: 0.1,
+        }
+        .into(),
+    ]);
+    table.push(vec![
+        "Q,Q \"pair\"".into(),
+        Estimate {
+            value: 0.5,
+            standard_error: 0.0,
+        }
+        .into(),
+    ]);
+
+    assert_eq!(
+        table.render(Format::Text),
+        "Seed: 7\n\
+         Hand                  Win\n\
+         AhKh        25.00% ±19.60\n\
+         Q,Q \"pair\"         50.00%\n"
+    );
+    assert_eq!(
+        table.render(Format::Json),
+        "{\n  \"seed\": 7,\n  \"rows\": [\n    \
+         {\"hand\": \"AhKh\", \"win\": {\"value\": 0.25, \"standard_error\": 0.1}},\n    \
+         {\"hand\": \"Q,Q \\\"pair\\\"\", \"win\": {\"value\": 0.5, \"standard_error\": 0}}\n  \
+         ]\n}\n"
+    );
+    assert_eq!(
+        table.render(Format::Csv),
+        "seed,hand,win,win_standard_error\n7,AhKh,0.25,0.1\n7,\"Q,Q \"\"pair\"\"\",0.5,0\n"
+    );
+    let empty = Table::new(&["Hand", "Win"]).field("Seed", 7u64);
+    assert_eq!(empty.render(Format::Csv), "seed,hand,win\n7,,\n");
+
+    let report = SimulationConfig {
+        iterations: 100,
+        seed: Some(1),
+        ..SimulationConfig::default()
     }
+    .run()
+    .table();
+    assert_eq!(report.rows.len(), 10);
+    assert_eq!(report.columns[1], "Hand probability");
+
+    // Seeds are strings in JSON, which can't hold every u64 as a number
+    let none = Deck::from(vec![]);
+    let hands = [Deck::parse("AhKh").unwrap(), Deck::parse("QsQd").unwrap()];
+    let config = EquityConfig {
+        max_runouts: 0,
+        samples: 10,
+        seed: Some(u64::MAX),
+        ..EquityConfig::default()
+    };
+    let json = Deck::equity(&hands, &none, &none, &config)
+        .table()
+        .render(Format::Json);
+    assert!(json.contains("\"seed\": \"18446744073709551615\""));
+}
+
+#[test]
+pub fn outs_of_a_set_by_the_river() {
+    let hand = Deck::parse("9c9d").unwrap();
+    let board = Deck::parse("9h5s2c").unwrap();
+    let outs = Deck::outs(&hand, &board, &[]);
+
+    // Quads with the last nine, a full house when the five or two pairs
+    let cards: Vec<_> = outs
+        .outs
+        .iter()
+        .map(|out| format!("{:#}", out.card))
+        .collect();
+    assert_eq!(cards, ["9s", "5h", "5d", "5c", "2s", "2h", "2d"]);
+    assert!(outs.outs.iter().all(|out| out.clean));
+    assert_eq!(outs.unseen, 47);
+
+    // Turn and river of ten other ranks that don't pair each other miss
+    let runouts = 47.0 * 46.0 / 2.0;
+    assert_eq!(outs.by_river, 1.0 - (780.0 - 60.0) / runouts);
+    assert_eq!(outs.runner_runner, 60.0 / runouts);
+    assert_eq!(outs.clean_by_river, outs.by_river);
+}
+
+#[test]
+pub fn outs_that_help_an_opponent_are_not_clean() {
+    let hand = Deck::parse("AhKh").unwrap();
+    let board = Deck::parse("7h8h2c").unwrap();
+    let opponent = [Range::parse("QsQd").unwrap()];
+    let outs = Deck::outs(&hand, &board, &opponent);
+
+    let out = |card| {
+        outs.outs
+            .iter()
+            .find(|out| format!("{:#}", out.card) == card)
+            .unwrap()
+    };
+    // Queens make a set, still below the flush
+    assert!(out("Qh").clean);
+    assert!(matches!(out("Qh").hand, Hand::Flush(..)));
+    assert!(out("As").clean);
+    assert_eq!(outs.unseen, 45);
+    assert!(outs.clean_by_river < outs.by_river);
+
+    // An ace pairs us but fills the opponent's flush draw
+    let hand = Deck::parse("AcKc").unwrap();
+    let opponent = [Range::parse("QhJh").unwrap()];
+    let outs = Deck::outs(&hand, &board, &opponent);
+    let out = |card| {
+        outs.outs
+            .iter()
+            .find(|out| format!("{:#}", out.card) == card)
+            .unwrap()
+    };
+    assert!(!out("Ah").clean);
+    assert!(out("As").clean);
+    let hand = Deck::parse("AhKh").unwrap();
+
+    let opponent = [Range::parse("QsQd").unwrap()];
+    let turn = Deck::parse("7h8h2c5c").unwrap();
+    let outs = Deck::outs(&hand, &turn, &opponent);
+    assert_eq!(outs.by_river, outs.outs.len() as f64 / 44.0);
+    assert_eq!(outs.runner_runner, 0.0);
+
+    let river = Deck::parse("7h8h2c5c9d").unwrap();
+    assert_eq!(
+        Deck::try_outs(&hand, &river, &opponent).unwrap_err(),
+        PokerError::OutsBoard(5)
+    );
+}
+
+#[test]
+pub fn cards_that_only_pair_the_board_are_not_outs() {
+    let hand = Deck::parse("AhKd").unwrap();
+    let board = Deck::parse("7s7c2d").unwrap();
+    let outs = Deck::outs(&hand, &board, &[]);
+
+    // Trips or two pair on the board play for everyone, aces and kings don't
+    let cards: Vec<_> = outs
+        .outs
+        .iter()
+        .map(|out| format!("{:#}", out.card))
+        .collect();
+    assert_eq!(cards, ["As", "Ad", "Ac", "Ks", "Kh", "Kc"]);
+    assert!(outs.outs.iter().all(|out| out.clean));
+
+    // Runouts without an ace or king only improve the board, like 9-9 or 7-2
+    let runouts = 47.0 * 46.0 / 2.0;
+    let blanks = 41.0 * 40.0 / 2.0;
+    assert_eq!(outs.by_river, (runouts - blanks) / runouts);
+    assert_eq!(outs.runner_runner, 0.0);
+
+    let hand = Deck::parse("AhKh").unwrap();
+    let board = Deck::parse("7h8h2c").unwrap();
+    let outs = Deck::outs(&hand, &board, &[]);
+    assert!(outs.outs.iter().all(|out| !matches!(
+        format!("{:#}", out.card).as_str(),
+        "2s" | "2d" | "7s" | "7d" | "7c" | "8s" | "8d" | "8c"
+    )));
+    assert_eq!(outs.outs.len(), 9 + 6);
+
+    // Opponent combos holding a card of a known opponent hand are dropped
+    let opponents = [Range::parse("QsQd").unwrap(), Range::parse("QQ").unwrap()];
+    assert!(Deck::try_outs(&hand, &board, &opponents).is_ok());
+    let opponents = [Range::parse("QsQd").unwrap(), Range::parse("QsQc").unwrap()];
+    assert_eq!(
+        Deck::try_outs(&hand, &board, &opponents).unwrap_err(),
+        PokerError::EmptyRange(0)
+    );
+}
+
+#[test]
+pub fn pot_odds_and_implied_odds() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    let call = Call {
+        pot: 100.0,
+        to_call: 50.0,
+        stack: None,
+    };
+
+    let draw = call.odds(0.2);
+    assert!(close(draw.break_even, 1.0 / 3.0));
+    assert!(close(draw.ev, -20.0));
+    assert!(close(draw.implied, 100.0));
+    assert!(!draw.profitable);
+
+    let deep = Call {
+        stack: Some(200.0),
+        ..call
+    };
+    assert!(deep.odds(0.2).profitable);
+    let short = Call {
+        stack: Some(120.0),
+        ..call
+    };
+    assert!(!short.odds(0.2).profitable);
+
+    let favourite = call.odds(0.4);
+    assert!(close(favourite.ev, 10.0));
+    assert_eq!(favourite.implied, 0.0);
+    assert!(favourite.profitable);
+    assert_eq!(call.odds(0.0).implied, f64::INFINITY);
+
+    for equity in [-0.1, 1.5, f64::NAN] {
+        assert!(matches!(
+            call.try_odds(equity),
+            Err(PokerError::InvalidOdds(_))
+        ));
+    }
+    for (pot, to_call) in [(0.0, 50.0), (100.0, 0.0), (-100.0, 50.0), (100.0, -1.0)] {
+        let call = Call {
+            pot,
+            to_call,
+            stack: None,
+        };
+        assert!(matches!(
+            call.try_odds(0.5),
+            Err(PokerError::InvalidOdds(_))
+        ));
+    }
+
+    // Nine flush cards and six overcards with no opponent to help
+    let hand = Deck::parse("AhKh").unwrap();
+    let board = Deck::parse("7h8h2c5c").unwrap();
+    let outs = Deck::outs(&hand, &board, &[]);
+    assert_eq!(outs.next_card(), 15.0 / 46.0);
+}
+
+#[test]
+pub fn short_deck_rankings() {
+    let short = Rules::ShortDeck {
+        trips_beat_straight: false,
+    };
+    let set = |s: &str| CardSet::from(&Deck::parse(s).unwrap());
+
+    // A-6-7-8-9 wraps into the lowest straight
+    let wheel = set("Ah6c7d8s9hKcKd");
+    assert_eq!(wheel.evaluate().category(), 1);
+    let straight = wheel.evaluate_with(short);
+    assert_eq!(straight.category(), 4);
+    assert!(straight < set("6c7d8s9hTsKcKd").evaluate_with(short));
+    assert_same_cards(
+        wheel.best_hand_with(short),
+        Hand::Straight(["A ♥", "6 ♣", "7 ♦", "8 ♠", "9 ♥"].map(card_parse)),
+    );
+    let suited = set("Ah6h7h8h9hKcKd").evaluate_with(short);
+    assert_eq!(suited.category(), 8);
+
+    // Flush over full house
+    let flush = set("AhJh9h7h6h").evaluate_with(short);
+    let full_house = set("AsAcAdKsKc").evaluate_with(short);
+    assert!(flush > full_house);
+    assert!(set("AhJh9h7h6h").evaluate() < set("AsAcAdKsKc").evaluate());
+
+    // Trips over a straight only when asked for, in the same seven cards
+    let both = set("6s6h6d7c8c9dTs");
+    assert_eq!(both.evaluate_with(short).category(), 4);
+    let trips = Rules::ShortDeck {
+        trips_beat_straight: true,
+    };
+    assert_eq!(both.evaluate_with(trips).category(), 3);
+    assert!(set("6s6h6d7cKc").evaluate_with(trips) > set("6c7d8s9hTs").evaluate_with(trips));
+
+    // Hands rank under the rules they were made with
+    let flush = set("AhJh9h7h6h").best_hand_with(short);
+    let full_house = set("AsAcAdKsKc").best_hand_with(short);
+    assert!(flush.strength_with(short) > full_house.strength_with(short));
+    assert!(flush < full_house);
+    let table = Deck::parse("6c7d8sKsKd").unwrap();
+    let hand = Deck::best_hand_with(&table, Deck::parse("Ah9h").unwrap(), short);
+    assert_eq!(hand.name(), "Straight");
+    assert_eq!(
+        hand.strength_with(short),
+        set("6c7d8sKsKdAh9h").evaluate_with(short)
+    );
+
+    // Short deck hands parse back from what they print
+    let wheel = set("Ah6c7d8s9hKcKd").best_hand_with(short);
+    assert_same_cards(wheel.to_string().parse().unwrap(), wheel);
+    assert_same_cards(format!("{:#}", wheel).parse().unwrap(), wheel);
+    assert!("Straight(Ah 6s 7d 8c Ts)".parse::<Hand>().is_err());
+
+    // Outs come from the short deck and count its wheel
+    let hand = Deck::parse("AhKh").unwrap();
+    let board = Deck::parse("7h8h6cJs").unwrap();
+    let outs = Deck::outs_with(&hand, &board, &[], short);
+    assert_eq!(outs.unseen, 30);
+    assert_eq!(outs.outs.len(), 14);
+    assert_eq!(outs.outs[0].hand.name(), "Flush");
+    let nine = outs
+        .outs
+        .iter()
+        .find(|out| out.card == card_parse("9s"))
+        .unwrap();
+    assert_eq!(nine.hand.name(), "Straight");
+    let standard = Deck::outs(&hand, &board, &[]);
+    assert_eq!(standard.unseen, 46);
+    assert!(standard
+        .outs
+        .iter()
+        .all(|out| out.card.rank != Rank::Nine || out.card.suit == Suit::Hearts));
+}
+
+#[test]
+pub fn short_deck_equity_and_simulation() {
+    let rules = Rules::ShortDeck {
+        trips_beat_straight: false,
+    };
+    let hands = [Deck::parse("AhKh").unwrap(), Deck::parse("QsQd").unwrap()];
+    let board = Deck::parse("9h8h7c").unwrap();
+    let config = EquityConfig {
+        rules,
+        ..EquityConfig::default()
+    };
+    let equity = Deck::equity(&hands, &board, &Deck::from(vec![]), &config);
+    assert!(equity.exact);
+    assert_eq!(equity.runouts, 29 * 28 / 2);
+
+    // Combos with cards below six are never dealt
+    let ranges = [
+        Range::parse("AA").unwrap(),
+        Range::parse("22, 55, 66").unwrap(),
+    ];
+    let equity = Range::equity(&ranges, &board, &Deck::from(vec![]), &config);
+    assert!(equity.combos[1]
+        .iter()
+        .all(|(combo, _)| combo.cards[0].rank == Rank::Six));
+
+    let report = SimulationConfig {
+        players: 6,
+        iterations: 2_000,
+        seed: Some(5),
+        rules,
+        ..SimulationConfig::default()
+    }
+    .run();
+    assert_eq!(report.iterations, 2_000);
+    assert!(report.hands[0].games() < report.hands[4].games());
+}
+
+#[test]
+pub fn omaha_uses_exactly_two_hole_cards() {
+    let board = Deck::parse("AhKhQhJh2c").unwrap();
+    let hand = Deck::parse("Th3s4s5s").unwrap();
+
+    // A royal flush in hold'em, nothing with only one hole card in Omaha
+    let hole = CardSet::from(&hand);
+    let table = CardSet::from(&board);
+    assert_eq!((hole | table).evaluate().category(), 9);
+    let omaha = Game::Omaha {
+        hole_cards: 4,
+        hi_lo: false,
+    };
+    assert_eq!(omaha.evaluate(hole, table, Rules::Standard).category(), 0);
+    assert!(matches!(
+        Deck::omaha_best_hand(&board, hand),
+        Hand::HighCard(..)
+    ));
+
+    let hand = Deck::parse("AsAd9s6c5c").unwrap();
+    let best = Deck::omaha_best_hand(&board, hand);
+    assert_same_cards(
+        best,
+        "ThreeOfAKind(As Ah Ad Kh Qh)".parse::<Hand>().unwrap(),
+    );
+
+    assert_eq!(
+        Deck::try_omaha_best_hand(&board, Deck::parse("AsAd").unwrap()).unwrap_err(),
+        PokerError::OmahaHandSize(2)
+    );
+}
+
+#[test]
+pub fn omaha_equity_and_simulation() {
+    let omaha = Game::Omaha {
+        hole_cards: 4,
+        hi_lo: false,
+    };
+    let config = EquityConfig {
+        game: omaha,
+        ..EquityConfig::default()
+    };
+    let hands = [
+        Deck::parse("AhKhQsJs").unwrap(),
+        Deck::parse("9c9d8c7d").unwrap(),
+    ];
+    let board = Deck::parse("9h8h2c").unwrap();
+    let none = Deck::from(vec![]);
+
+    let equity = Deck::equity(&hands, &board, &none, &config);
+    assert!(equity.exact);
+    assert_eq!(equity.runouts, 41 * 40 / 2);
+    let total: f64 = equity.players.iter().map(Tally::equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!(equity.players[1].equity() > equity.players[0].equity());
+
+    let holdem = EquityConfig::default();
+    assert_eq!(
+        Deck::try_equity(&hands, &board, &none, &holdem).unwrap_err(),
+        PokerError::HandSize(4)
+    );
+
+    let report = SimulationConfig {
+        players: 6,
+        iterations: 500,
+        seed: Some(2),
+        game: Game::Omaha {
+            hole_cards: 6,
+            hi_lo: false,
+        },
+        ..SimulationConfig::default()
+    }
+    .run();
+    let games: usize = report.hands.iter().map(Tally::games).sum();
+    assert_eq!(games, 6 * 500);
+}
+
+#[test]
+pub fn eight_or_better_lows() {
+    let low = |cards: &str| CardSet::from(&Deck::parse(cards).unwrap()).low_eight();
+
+    let eight = low("8h5c4d3sAhKdKc").unwrap();
+    assert_eq!(eight.to_string(), "8-5-4-3-A");
+    assert_eq!(low("AhAs2c3d4h9sKc"), None);
+    assert_eq!(low("2c2d3h4s5hKdKc"), None);
+
+    // Straights and flushes don't spoil a low, the best five count
+    let wheel = low("5h4h3h2hAh").unwrap();
+    assert_eq!(wheel.to_string(), "5-4-3-2-A");
+    let six = low("Ah2c3d4s6h7c8d").unwrap();
+    assert_eq!(six.to_string(), "6-4-3-2-A");
+    assert!(wheel > six && six > eight);
+    assert!(low("7h6c4d3s2h").unwrap() < six);
+}
+
+#[test]
+pub fn hi_lo_splits_each_half() {
+    let wheel = CardSet::from(&Deck::parse("5h4h3h2hAh").unwrap()).low_eight();
+    let strengths = vec![Strength(1), Strength(2), Strength(3)];
+
+    // The best high takes half, two tied lows a quarter each
+    let showdown = Showdown::hi_lo(strengths.clone(), vec![wheel, wheel, None]);
+    assert_eq!(showdown.low_winners, vec![0, 1]);
+    let shares: Vec<_> = (0..3).map(|player| showdown.share(player)).collect();
+    assert_eq!(shares, vec![0.25, 0.25, 0.5]);
+
+    // Without a qualifying low the high hand scoops
+    let showdown = Showdown::hi_lo(strengths, vec![None; 3]);
+    assert!(showdown.low_winners.is_empty());
+    assert_eq!(showdown.share(2), 1.0);
+
+    let record = |showdown: &Showdown, player| {
+        let mut split = Split::default();
+        split.record(showdown, player);
+        split
+    };
+
+    // The sole high with a tied low takes three quarters, not a scoop
+    let showdown = Showdown::hi_lo(
+        vec![Strength(3), Strength(1), Strength(2)],
+        vec![wheel, wheel, None],
+    );
+    assert_eq!(showdown.share(0), 0.75);
+    let three_quarters = Split {
+        high_alone: 1,
+        low_shared: 1,
+        ..Split::default()
+    };
+    assert_eq!(record(&showdown, 0), three_quarters);
+    let quarter = Split {
+        low_only: 1,
+        low_shared: 1,
+        ..Split::default()
+    };
+    assert_eq!(record(&showdown, 1), quarter);
+    assert_eq!(record(&showdown, 2), Split::default());
+
+    // A tied high without a low is shared, and neither player scoops
+    let showdown = Showdown::hi_lo(vec![Strength(2), Strength(2), Strength(1)], vec![None; 3]);
+    let tied = Split {
+        high_only: 1,
+        high_shared: 1,
+        ..Split::default()
+    };
+    assert_eq!(record(&showdown, 0), tied);
+    assert_eq!(record(&showdown, 1), tied);
+
+    // Winning both halves alone is a scoop that counts in each half too
+    let showdown = Showdown::hi_lo(
+        vec![Strength(3), Strength(1), Strength(2)],
+        vec![wheel, None, None],
+    );
+    let scoop = Split {
+        scoops: 1,
+        high_alone: 1,
+        low_alone: 1,
+        ..Split::default()
+    };
+    assert_eq!(record(&showdown, 0), scoop);
+}
+
+#[test]
+pub fn omaha_hi_lo_equity_and_simulation() {
+    let game = Game::Omaha {
+        hole_cards: 4,
+        hi_lo: true,
+    };
+    assert!(game.is_hi_lo());
+    let config = EquityConfig {
+        game,
+        ..EquityConfig::default()
+    };
+    let hands = [
+        Deck::parse("KsKdQsQd").unwrap(),
+        Deck::parse("Ah2s4c5d").unwrap(),
+    ];
+    let board = Deck::parse("Kh7c3s").unwrap();
+    let none = Deck::from(vec![]);
+
+    let equity = Deck::equity(&hands, &board, &none, &config);
+    let total: f64 = equity.players.iter().map(Tally::equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert_eq!(equity.splits.len(), 2);
+    assert_eq!(equity.splits[0].low_alone + equity.splits[0].low_shared, 0);
+    assert!(equity.splits[1].low_alone > 0);
+    assert_eq!(equity.splits[0].low_only, 0);
+    assert!(equity.splits[1].low_only > 0);
+    assert!(equity.splits[0].scoops > equity.splits[1].scoops);
+
+    let table = equity.table();
+    assert_eq!(
+        table.columns[4..],
+        [
+            "Scoop",
+            "High only",
+            "Low only",
+            "High alone",
+            "High shared",
+            "Low alone",
+            "Low shared"
+        ]
+    );
+    let high = EquityConfig {
+        game: Game::Omaha {
+            hole_cards: 4,
+            hi_lo: false,
+        },
+        ..config
+    };
+    assert!(Deck::equity(&hands, &board, &none, &high).splits.is_empty());
+
+    let report = SimulationConfig {
+        players: 4,
+        iterations: 500,
+        seed: Some(3),
+        game,
+        ..SimulationConfig::default()
+    }
+    .run();
+    let games: usize = report.hands.iter().map(Tally::games).sum();
+    assert_eq!(games, 4 * 500);
+}
+
+#[test]
+pub fn ace_to_five_lowball() {
+    let low = |cards: &str| {
+        CardSet::from(&Deck::parse(cards).unwrap()).evaluate_lowball(Lowball::AceToFive)
+    };
+
+    // Straights and flushes don't count, the ace is the lowest card
+    let wheel = low("5h4h3h2hAh");
+    assert_eq!(wheel.category(), 0);
+    assert_eq!(
+        wheel.ranks(),
+        [Rank::Five, Rank::Four, Rank::Three, Rank::Two, Rank::Ace]
+    );
+    assert!(wheel < low("6c4d3s2hAc"));
+    assert!(low("6c4d3s2hAc") < low("6c5d3s2hAc"));
+    assert!(low("KcQdJsTh9c") < low("AcAd2s3h4c"));
+
+    // Razz plays the best five of seven
+    let razz = low("AcAdKs7h5c3d2h");
+    assert_eq!(razz.category(), 0);
+    assert_eq!(razz.ranks()[0], Rank::Seven);
+    assert_eq!(low("KcKdKsQhQc").category(), 6);
+}
+
+#[test]
+pub fn deuce_to_seven_lowball() {
+    let low = |cards: &str| {
+        CardSet::from(&Deck::parse(cards).unwrap()).evaluate_lowball(Lowball::DeuceToSeven)
+    };
+
+    let number_one = low("7c5d4s3h2c");
+    assert!(number_one < low("8c5d4s3h2c"));
+    // Straights and flushes count, aces are high and A-2-3-4-5 is no straight
+    assert!(low("8c5d4s3h2c") < low("7c6d5s4h3c"));
+    assert!(low("KcQdJs9h8c") < low("7h5h4h3h2h"));
+    let ace_high = low("Ac5d4s3h2c");
+    assert_eq!(ace_high.category(), 0);
+    assert_eq!(ace_high.ranks()[0], Rank::Ace);
+    assert!(low("KcQdJs9h8c") < ace_high);
+}
+
+#[test]
+pub fn lowball_showdown_picks_the_lowest() {
+    let hands = [
+        Deck::parse("7c5d4s3h2c").unwrap(),
+        Deck::parse("7d5c4h3s2d").unwrap(),
+        Deck::parse("8h6h4c3d2s").unwrap(),
+    ];
+    let showdown = Deck::lowball_showdown(&hands, Lowball::DeuceToSeven);
+    assert_eq!(showdown.winners, vec![0, 1]);
+    assert_eq!(showdown.share(1), 0.5);
+
+    let showdown = Deck::lowball_showdown(&hands[1..], Lowball::AceToFive);
+    assert_eq!(showdown.winners, vec![0]);
+
+    assert_eq!(
+        Deck::try_lowball_showdown(&[Deck::parse("7c5d4s3h").unwrap()], Lowball::AceToFive)
+            .unwrap_err(),
+        PokerError::LowballHandSize(4)
+    );
+}
+
+#[test]
+pub fn badugi_picks_the_best_sub_hand() {
+    let badugi = |cards: &str| Deck::badugi(&Deck::parse(cards).unwrap());
+
+    let wheel = badugi("4s3h2dAc");
+    assert_eq!(wheel.len(), 4);
+    assert_eq!(wheel.to_string(), "4-3-2-A");
+    assert!(wheel > badugi("Ks3h2dAc"));
+
+    // A pair or a repeated suit drops a card, whichever leaves the lowest
+    let three = badugi("Ks2s3hAd");
+    assert_eq!(three.len(), 3);
+    assert_eq!(three.ranks(), vec![Rank::Three, Rank::Two, Rank::Ace]);
+    assert!(badugi("KsQhJdTc") > three);
+    assert!(three > badugi("AsAh2s2h"));
+    assert_eq!(badugi("AsAh2s2h").to_string(), "2-A");
+    assert_eq!(badugi("KsQsJsTs").len(), 1);
+    assert!(Badugi::default() < badugi("KsQsJsTs"));
+
+    assert_eq!(
+        Deck::try_badugi(&Deck::parse("AsKh2c").unwrap()).unwrap_err(),
+        PokerError::BadugiHandSize(3)
+    );
+}
+
+#[test]
+pub fn badugi_simulation_and_equity() {
+    let report = SimulationConfig {
+        players: 3,
+        iterations: 1_000,
+        seed: Some(4),
+        game: Game::Badugi,
+        ..SimulationConfig::default()
+    }
+    .run();
+    assert_eq!(report.hands.len(), 4);
+    let games: usize = report.hands.iter().map(Tally::games).sum();
+    assert_eq!(games, 3 * 1_000);
+    assert_eq!(report.table().rows[3][0], "Badugi".into());
+
+    let config = EquityConfig {
+        game: Game::Badugi,
+        ..EquityConfig::default()
+    };
+    let hands = [
+        Deck::parse("4s3h2dAc").unwrap(),
+        Deck::parse("KsQhJdTc").unwrap(),
+    ];
+    let none = Deck::from(vec![]);
+    assert_eq!(
+        Deck::try_equity(&hands, &none, &none, &config).unwrap_err(),
+        PokerError::NoBoard
+    );
+}
+
+#[test]
+pub fn stud_deals_up_and_down_by_street() {
+    let mut deck = Deck::french().shuffle_seeded(5);
+    let mut hands = vec![StudHand::default(); 7];
+
+    deck.deal_street(&mut hands, 3);
+    assert!(hands
+        .iter()
+        .all(|hand| hand.down.cards.len() == 2 && hand.up.cards.len() == 1));
+    for street in 4..=7 {
+        deck.deal_street(&mut hands, street);
+    }
+    assert!(hands
+        .iter()
+        .all(|hand| hand.down.cards.len() == 3 && hand.up.cards.len() == 4));
+    assert_eq!(deck.cards.len(), 52 - 7 * 7);
+    assert_eq!(
+        Face::STUD.iter().filter(|&&face| face == Face::Up).count(),
+        4
+    );
+
+    assert_eq!(
+        deck.try_deal_street(&mut hands, 8).unwrap_err(),
+        PokerError::StudStreet(8)
+    );
+    let mut eight = vec![StudHand::default(); 8];
+    let mut deck = Deck::french();
+    deck.deal(45);
+    assert_eq!(
+        deck.try_deal_street(&mut eight, 4).unwrap_err(),
+        PokerError::NotEnoughCards {
+            requested: 8,
+            left: 7
+        }
+    );
+}
+
+#[test]
+pub fn stud_plays_the_best_five_of_seven() {
+    let hand: StudHand = "AsKs/2h3c4dQsJs".parse().unwrap();
+    assert_eq!(hand.len(), 7);
+    assert_eq!(format!("{:#}", hand), "AsKs/2h3c4dQsJs");
+    assert!(matches!(hand.best_hand(), Some(Hand::HighCard(..))));
+
+    let hand: StudHand = "AsKsTs/2h3cQsJs".parse().unwrap();
+    assert!(matches!(hand.best_hand(), Some(Hand::RoyalFlush(..))));
+    assert_eq!(hand.evaluate().category(), 9);
+
+    // Third and fourth street hold no five card hand yet, but still rank
+    let third: StudHand = "AsKd/Qh".parse().unwrap();
+    assert!(third.best_hand().is_none());
+    assert_eq!(third.evaluate().category(), 0);
+    assert!(third.evaluate() < "2s2d/3h".parse::<StudHand>().unwrap().evaluate());
+    assert!(third.evaluate() > "AhKc/Jh".parse::<StudHand>().unwrap().evaluate());
+
+    let fourth: StudHand = "AsKd/QhQc".parse().unwrap();
+    assert!(fourth.best_hand().is_none());
+    let strength = fourth.evaluate();
+    assert_eq!(strength.category(), 1);
+    assert_eq!(
+        strength.ranks()[..4],
+        [Rank::Queen, Rank::Queen, Rank::Ace, Rank::King]
+    );
+    assert!(strength < "2s2d/3h3c".parse::<StudHand>().unwrap().evaluate());
+
+    let mut deck = Deck::french().shuffle_seeded(8);
+    let mut hands = vec![StudHand::default(); 2];
+    deck.deal_street(&mut hands, 3);
+    deck.deal_street(&mut hands, 4);
+    assert!(hands.iter().all(|hand| hand.best_hand().is_none()));
+    deck.deal_street(&mut hands, 5);
+    assert!(hands.iter().all(|hand| hand.best_hand().is_some()));
+}
+
+#[test]
+pub fn stud_equity_with_visible_up_cards() {
+    let config = EquityConfig {
+        seed: Some(6),
+        samples: 2_000,
+        ..EquityConfig::default()
+    };
+    let none = Deck::default();
+
+    // Every card known, the flush beats two pair
+    let hands = [
+        StudHand::parse("AhKh/2h3h9h7d5s").unwrap(),
+        StudHand::parse("QsQd/JcJd4c6c8s").unwrap(),
+    ];
+    let equity = Deck::stud_equity(&hands, &none, &config);
+    assert!(equity.exact);
+    assert_eq!(equity.runouts, 1);
+    assert_eq!(equity.players[0].wins, 1);
+
+    // Opponents show only up cards, which no one else can draw
+    let hands = [
+        StudHand::parse("AsAd/Ah7c").unwrap(),
+        StudHand::parse("/QhQs2c").unwrap(),
+        StudHand::parse("/Ac9d").unwrap(),
+    ];
+    let equity = Deck::stud_equity(&hands, &none, &config);
+    assert!(!equity.exact);
+    let total: f64 = equity.players.iter().map(Tally::equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!(equity.players[0].equity() > 0.5);
+    assert!(equity.splits.is_empty());
+
+    let stud8 = EquityConfig {
+        game: Game::Stud { hi_lo: true },
+        ..config
+    };
+    let equity = Deck::stud_equity(&hands, &none, &stud8);
+    assert_eq!(equity.splits.len(), 3);
+    let high_alone: usize = equity.splits.iter().map(|split| split.high_alone).sum();
+    assert!(high_alone <= equity.runouts);
+    assert!(equity
+        .splits
+        .iter()
+        .all(|split| split.scoops <= split.high_alone));
+
+    assert_eq!(
+        Deck::try_stud_equity(&[StudHand::parse("AsAd/AhAs").unwrap()], &none, &config)
+            .unwrap_err(),
+        PokerError::DuplicateCard(card_parse("As"))
+    );
+    assert_eq!(
+        Deck::try_stud_equity(
+            &[StudHand::parse("AsAd/Ah7c2c3c4c5c").unwrap()],
+            &none,
+            &config
+        )
+        .unwrap_err(),
+        PokerError::StudHandSize(8)
+    );
 
-    #[test]
-    pub fn royal_flush() {
-        let table = Deck::from(vec![
-            card_parse("10 ♠"),
-            card_parse("J ♠"),
-            card_parse("Q ♠"),
-            card_parse("K ♠"),
-            card_parse("A ♠"),
-        ]);
-
-        let hand = Deck::from(vec![card_parse("9 ♠"), card_parse("8 ♠")]);
-
-        let hand = Deck::best_hand(&table, hand);
-        let expected = Hand::RoyalFlush([
-            card_parse("10 ♠"),
-            card_parse("J ♠"),
-            card_parse("Q ♠"),
-            card_parse("K ♠"),
-            card_parse("A ♠"),
-        ]);
-        assert_eq!(hand, expected);
+    let report = SimulationConfig {
+        players: 7,
+        iterations: 300,
+        seed: Some(7),
+        game: Game::Stud { hi_lo: true },
+        ..SimulationConfig::default()
     }
+    .run();
+    let games: usize = report.hands.iter().map(Tally::games).sum();
+    assert_eq!(games, 7 * 300);
 }